@@ -0,0 +1,277 @@
+use std::num::ParseIntError;
+use std::path::Path;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use dioxus_fullstack::prelude::ServerFnError;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{percentage, DownloadItem};
+use crate::apis::download_item_details::{DownloadItemDetails, TorrentDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+
+const PAGE_SIZE: i64 = 1000;
+const DOWNLOAD_KEYS: &[&str] = &[
+    "gid",
+    "status",
+    "totalLength",
+    "completedLength",
+    "downloadSpeed",
+    "uploadSpeed",
+    "errorMessage",
+    "files",
+    "bittorrent",
+    "numSeeders",
+    "connections",
+    "uploadLength",
+    "dir",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub gid: String,
+    pub status: String,
+    pub total_length: String,
+    pub completed_length: String,
+    pub download_speed: String,
+    pub upload_speed: String,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub files: Vec<File>,
+    #[serde(default)]
+    pub bittorrent: Option<BitTorrent>,
+    /// Only sent for `BitTorrent` downloads
+    #[serde(default)]
+    pub num_seeders: Option<String>,
+    pub connections: String,
+    pub upload_length: String,
+    pub dir: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct File {
+    pub path: String,
+    #[serde(default)]
+    pub uris: Vec<Uri>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Uri {
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BitTorrent {
+    pub info: Option<Info>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Info {
+    pub name: String,
+}
+
+impl Item {
+    /// The torrent's name, or failing that the file we're writing, or failing that where it's from
+    fn display_name(&self) -> String {
+        let torrent_name = self
+            .bittorrent
+            .as_ref()
+            .and_then(|bittorrent| bittorrent.info.as_ref())
+            .map(|info| info.name.clone());
+        let file_name = self.files.first().and_then(|file| {
+            Path::new(&file.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        });
+        let uri = self
+            .files
+            .first()
+            .and_then(|file| file.uris.first())
+            .map(|uri| uri.uri.clone());
+
+        torrent_name
+            .or(file_name)
+            .or(uri)
+            .unwrap_or_else(|| self.gid.clone())
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(untagged)]
+enum Param<'a> {
+    Text(&'a str),
+    Number(i64),
+    Keys(&'a [&'a str]),
+}
+
+#[derive(Debug, Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'a str,
+    id: &'a str,
+    method: &'a str,
+    params: Vec<Param<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Debug)]
+pub struct Aria2 {
+    name: String,
+    endpoint: Url,
+    client: Client,
+    token: Option<String>,
+}
+
+impl Aria2 {
+    pub fn new(name: String, endpoint: Url, secret: Option<String>) -> Self {
+        Self {
+            name,
+            endpoint,
+            client: default_reqwest_client(),
+            token: secret.map(|secret| format!("token:{secret}")),
+        }
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let page = [Param::Number(0), Param::Number(PAGE_SIZE)];
+        let (active, waiting, stopped) = futures_util::future::try_join3(
+            self.call("aria2.tellActive", &[]),
+            self.call("aria2.tellWaiting", &page),
+            self.call("aria2.tellStopped", &page),
+        )
+        .await?;
+
+        let mut items = active;
+        items.extend(waiting);
+        items.extend(stopped);
+        Ok(items)
+    }
+
+    async fn call(&self, method: &str, params: &[Param<'_>]) -> Result<Vec<Item>, ServerFnError> {
+        let mut all_params = vec![];
+        if let Some(token) = &self.token {
+            all_params.push(Param::Text(token));
+        }
+        all_params.extend(params.iter().copied());
+        all_params.push(Param::Keys(DOWNLOAD_KEYS));
+
+        let response = self
+            .client
+            .post(self.rpc_url()?)
+            .json(&RpcRequest {
+                jsonrpc: "2.0",
+                id: "imaginative-balaji",
+                method,
+                params: all_params,
+            })
+            .send()
+            .await?
+            .json::<RpcResponse<Vec<Item>>>()
+            .await?;
+
+        match response {
+            RpcResponse {
+                error: Some(error), ..
+            } => Err(ServerFnError::ServerError(error.message)),
+            RpcResponse { result, .. } => Ok(result.unwrap_or_default()),
+        }
+    }
+
+    fn rpc_url(&self) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("jsonrpc");
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for Aria2 {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::Aria2
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseIntError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = u64::from_str(&self.total_length)?;
+        let done = u64::from_str(&self.completed_length)?;
+        let download_speed = u64::from_str(&self.download_speed)?;
+        // Only BitTorrent downloads upload anything, for the rest it would always be zero
+        let (upload_speed, details) = match self.bittorrent {
+            Some(_) => (
+                Some(u64::from_str(&self.upload_speed)?),
+                DownloadItemDetails::Torrent(TorrentDetails {
+                    ratio: Some(percentage(u64::from_str(&self.upload_length)?, done) / 100.0)
+                        .filter(|_| done > 0),
+                    seeds: self.num_seeders.as_deref().map(u64::from_str).transpose()?,
+                    peers: Some(u64::from_str(&self.connections)?),
+                    tracker: None,
+                    save_path: Some(self.dir.clone()),
+                }),
+            ),
+            None => (None, DownloadItemDetails::None),
+        };
+
+        Ok(DownloadItem {
+            download_item_id: format!("aria2-{}", self.gid),
+            download_item_name: self.display_name(),
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: done,
+            download_bytes_left: total.saturating_sub(done),
+            download_bytes_per_second: Some(download_speed),
+            upload_bytes_per_second: upload_speed,
+            download_eta: Eta::from_rate(total.saturating_sub(done), Some(download_speed)),
+            download_item_category: None,
+            download_item_tags: vec![],
+            download_item_details: details,
+            download_item_added_at: None,
+            download_item_completed_at: None,
+            download_queue_position: None,
+            download_percentage_complete: percentage(done, total),
+            download_item_status: match self.status.as_str() {
+                "active" => DownloadStatus::Downloading,
+                "waiting" => DownloadStatus::Queued,
+                "paused" => DownloadStatus::Paused,
+                "complete" => DownloadStatus::Completed,
+                "error" => DownloadStatus::Failed,
+                _ => DownloadStatus::Unknown(self.status.clone()),
+            },
+            download_item_status_message: self.error_message,
+        })
+    }
+}
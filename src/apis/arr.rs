@@ -0,0 +1,256 @@
+use std::num::ParseFloatError;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dioxus_fullstack::prelude::ServerFnError;
+use num_traits::cast::ToPrimitive;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{percentage, DownloadItem};
+use crate::apis::download_item_details::{DownloadItemDetails, MediaManagerDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+
+const API_KEY_HEADER: &str = "X-Api-Key";
+const PAGE_SIZE: &str = "1000";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub id: i64,
+    pub title: String,
+    pub size: f64,
+    pub sizeleft: f64,
+    #[serde(default)]
+    pub timeleft: Option<String>,
+    #[serde(default)]
+    pub tracked_download_state: Option<String>,
+    #[serde(default)]
+    pub status_messages: Vec<StatusMessage>,
+    #[serde(default)]
+    pub error_message: Option<String>,
+    #[serde(default)]
+    pub download_client: Option<String>,
+    #[serde(default)]
+    pub protocol: Option<String>,
+    #[serde(default)]
+    pub added: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub quality: Option<QualityModel>,
+    #[serde(default)]
+    pub series: Option<Series>,
+    #[serde(default)]
+    pub episode: Option<Episode>,
+    #[serde(default)]
+    pub movie: Option<Movie>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusMessage {
+    #[serde(default)]
+    pub messages: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QualityModel {
+    pub quality: Quality,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Quality {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Series {
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Episode {
+    pub season_number: i64,
+    pub episode_number: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Movie {
+    pub title: String,
+    pub year: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Queue {
+    records: Vec<Item>,
+}
+
+impl Item {
+    /// What the media manager thinks this is, rather than the release name
+    fn media_title(&self) -> String {
+        match (&self.series, &self.episode, &self.movie) {
+            (Some(series), Some(episode), _) => format!(
+                "{} S{:02}E{:02}",
+                series.title, episode.season_number, episode.episode_number
+            ),
+            (Some(series), None, _) => series.title.clone(),
+            (
+                _,
+                _,
+                Some(Movie {
+                    title,
+                    year: Some(year),
+                }),
+            ) => format!("{title} ({year})"),
+            (_, _, Some(movie)) => movie.title.clone(),
+            _ => self.title.clone(),
+        }
+    }
+}
+
+/// The download queue of Sonarr or Radarr, which both share the v3 queue API
+#[derive(Debug)]
+pub struct Arr {
+    name: String,
+    kind: DownloadClientKind,
+    endpoint: Url,
+    apikey: String,
+    client: Client,
+}
+
+impl Arr {
+    pub(crate) fn new(
+        name: String,
+        kind: DownloadClientKind,
+        endpoint: Url,
+        apikey: String,
+    ) -> Self {
+        Self {
+            name,
+            kind,
+            endpoint,
+            apikey,
+            client: default_reqwest_client(),
+        }
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        Ok(self
+            .client
+            .get(self.queue_url()?)
+            .header(API_KEY_HEADER, &self.apikey)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Queue>()
+            .await?
+            .records)
+    }
+
+    fn queue_url(&self) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("api")
+            .push("v3")
+            .push("queue");
+        url.query_pairs_mut()
+            .append_pair("page", "1")
+            .append_pair("pageSize", PAGE_SIZE)
+            .append_pair("includeSeries", "true")
+            .append_pair("includeEpisode", "true")
+            .append_pair("includeMovie", "true")
+            .finish();
+        Ok(url)
+    }
+
+    const fn id_prefix(&self) -> &'static str {
+        match self.kind {
+            DownloadClientKind::Radarr => "radarr",
+            _ => "sonarr",
+        }
+    }
+}
+
+#[async_trait]
+impl DownloadClient for Arr {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        self.kind
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .map(|item| {
+                item.map(|mut item| {
+                    item.download_item_id =
+                        format!("{}-{}", self.id_prefix(), item.download_item_id);
+                    item
+                })
+            })
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseFloatError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = self.size.to_u64().unwrap_or_default();
+        let left = self.sizeleft.to_u64().unwrap_or_default();
+        let status = match self.tracked_download_state.as_deref() {
+            Some("downloading") => DownloadStatus::Downloading,
+            Some("importPending") => DownloadStatus::Queued,
+            Some("importing") => DownloadStatus::Moving,
+            Some("imported") => DownloadStatus::Completed,
+            Some("failedPending" | "failed") => DownloadStatus::Failed,
+            state => DownloadStatus::Unknown(state.unwrap_or_default().to_string()),
+        };
+        let messages = self
+            .status_messages
+            .iter()
+            .flat_map(|status| status.messages.iter().cloned())
+            .chain(self.error_message.clone())
+            .collect::<Vec<_>>();
+        let status_message = (!messages.is_empty()).then(|| messages.join("; "));
+
+        Ok(DownloadItem {
+            download_item_id: self.id.to_string(),
+            download_item_name: self.media_title(),
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: total.saturating_sub(left),
+            download_bytes_left: left,
+            download_bytes_per_second: None,
+            upload_bytes_per_second: None,
+            download_eta: self
+                .timeleft
+                .as_deref()
+                .map_or(Eta::Unknown, Eta::from_timeleft),
+            download_item_category: None,
+            download_item_tags: vec![],
+            download_item_details: DownloadItemDetails::MediaManager(MediaManagerDetails {
+                quality: self.quality.map(|quality| quality.quality.name),
+                protocol: self.protocol,
+                download_client: self.download_client,
+            }),
+            download_item_added_at: self.added,
+            download_item_completed_at: None,
+            download_queue_position: None,
+            download_percentage_complete: percentage(total.saturating_sub(left), total),
+            download_item_status: status,
+            download_item_status_message: status_message,
+        })
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::apis::aria2::Aria2;
+use crate::apis::arr::Arr;
+use crate::apis::deluge::Deluge;
+use crate::apis::download_client::{DisabledBackend, DownloadClient, DownloadClientKind};
+use crate::apis::nzbget::NzbGet;
+use crate::apis::qbittorrent::QBitTorrent;
+use crate::apis::registry::Registry;
+use crate::apis::rtorrent::RTorrent;
+use crate::apis::sabnzbd::Sabnzbd;
+use crate::apis::transmission::Transmission;
+
+const CONFIG_ENV: &str = "BALAJI_CONFIG";
+const CONFIG_ARG: &str = "--config";
+
+#[derive(Debug, Deserialize, Default)]
+pub struct Config {
+    #[serde(default, rename = "instance")]
+    pub instances: Vec<InstanceConfig>,
+}
+
+/// A backend as written in the config file
+///
+/// Everything is optional here so that we can report every problem with an instance at once,
+/// rather than stopping at the first field serde doesn't like.
+#[derive(Debug, Deserialize, Default)]
+pub struct InstanceConfig {
+    pub name: Option<String>,
+    pub kind: Option<String>,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub api_key: Option<String>,
+    pub secret: Option<String>,
+}
+
+impl Config {
+    /// Read the config file named by `--config` or `BALAJI_CONFIG`, falling back to the
+    /// `QBT_*` and `SAB_*` environment variables when neither is given
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = config_path() else {
+            return Ok(Self::from_env());
+        };
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|err| format!("Could not read config file {}: {err}", path.display()))?;
+        toml::from_str(&contents)
+            .map_err(|err| format!("Invalid config file {}: {err}", path.display()))
+    }
+
+    fn from_env() -> Self {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let mut instances = vec![];
+
+        if let Some(url) = var("QBT_URL") {
+            instances.push(InstanceConfig {
+                name: Some("qbittorrent".to_string()),
+                kind: Some("qbittorrent".to_string()),
+                url: Some(url),
+                username: var("QBT_USERNAME"),
+                password: var("QBT_PASSWORD"),
+                ..InstanceConfig::default()
+            });
+        }
+
+        if let Some(url) = var("SAB_URL") {
+            instances.push(InstanceConfig {
+                name: Some("sabnzbd".to_string()),
+                kind: Some("sabnzbd".to_string()),
+                url: Some(url),
+                api_key: var("SAB_API"),
+                ..InstanceConfig::default()
+            });
+        }
+
+        Self { instances }
+    }
+
+    /// Build a registry from the config, never failing
+    ///
+    /// Anything that can't be turned into a client is registered as disabled along with the
+    /// reasons why, so it can be shown on the dashboard.
+    pub fn load_registry() -> Registry {
+        match Self::load() {
+            Ok(config) => config.into_registry(),
+            Err(problem) => {
+                let mut registry = Registry::new();
+                registry.disable(DisabledBackend {
+                    name: "configuration".to_string(),
+                    kind: "config".to_string(),
+                    problems: vec![problem],
+                });
+                registry
+            }
+        }
+    }
+
+    pub fn into_registry(self) -> Registry {
+        let mut registry = Registry::new();
+        let mut names = HashSet::new();
+
+        for (index, instance) in self.instances.into_iter().enumerate() {
+            let name = instance
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("instance {}", index + 1));
+
+            // Items are routed back to their backend by name, so a second one would never be
+            // reached
+            if !names.insert(name.clone()) {
+                registry.disable(DisabledBackend {
+                    problems: vec![format!("Another instance is already named {name}")],
+                    name,
+                    kind: instance.kind.unwrap_or_default(),
+                });
+                continue;
+            }
+
+            match instance.build(&name) {
+                Ok(client) => registry.register(client),
+                Err(problems) => registry.disable(DisabledBackend {
+                    name,
+                    kind: instance.kind.unwrap_or_default(),
+                    problems,
+                }),
+            }
+        }
+
+        registry
+    }
+}
+
+impl InstanceConfig {
+    fn build(&self, name: &str) -> Result<Box<dyn DownloadClient>, Vec<String>> {
+        let mut validator = Validator::default();
+        validator.required("name", self.name.as_ref());
+        let url = validator.url(self.url.as_ref());
+
+        match self.kind.as_deref() {
+            Some("qbittorrent") => {
+                let username = validator.required("username", self.username.as_ref());
+                let password = validator.required("password", self.password.as_ref());
+                let ((url, username), password) =
+                    validator.finish(url.zip(username).zip(password))?;
+
+                Ok(Box::new(QBitTorrent::new(
+                    name.to_string(),
+                    url,
+                    username,
+                    password,
+                )))
+            }
+            Some("sabnzbd") => {
+                let api_key = validator.required("api_key", self.api_key.as_ref());
+                let (url, api_key) = validator.finish(url.zip(api_key))?;
+
+                Ok(Box::new(Sabnzbd::new(name.to_string(), url, api_key)))
+            }
+            Some("transmission") => Ok(Box::new(Transmission::new(
+                name.to_string(),
+                validator.finish(url)?,
+                self.username.clone(),
+                self.password.clone(),
+            ))),
+            Some("deluge") => {
+                let password = validator.required("password", self.password.as_ref());
+                let (url, password) = validator.finish(url.zip(password))?;
+
+                Ok(Box::new(Deluge::new(name.to_string(), url, password)))
+            }
+            Some("nzbget") => {
+                let username = validator.required("username", self.username.as_ref());
+                let password = validator.required("password", self.password.as_ref());
+                let ((url, username), password) =
+                    validator.finish(url.zip(username).zip(password))?;
+
+                Ok(Box::new(NzbGet::new(
+                    name.to_string(),
+                    url,
+                    username,
+                    password,
+                )))
+            }
+            Some("rtorrent") => Ok(Box::new(RTorrent::new(
+                name.to_string(),
+                validator.finish(url)?,
+                self.username.clone(),
+                self.password.clone(),
+            ))),
+            Some("aria2") => Ok(Box::new(Aria2::new(
+                name.to_string(),
+                validator.finish(url)?,
+                self.secret.clone(),
+            ))),
+            Some(kind @ ("sonarr" | "radarr")) => {
+                let api_key = validator.required("api_key", self.api_key.as_ref());
+                let (url, api_key) = validator.finish(url.zip(api_key))?;
+                let kind = if kind == "radarr" {
+                    DownloadClientKind::Radarr
+                } else {
+                    DownloadClientKind::Sonarr
+                };
+
+                Ok(Box::new(Arr::new(name.to_string(), kind, url, api_key)))
+            }
+            Some(kind) => {
+                validator
+                    .problems
+                    .push(format!("kind \"{kind}\" is not a supported backend"));
+                Err(validator.problems)
+            }
+            None => {
+                validator.problems.push("kind is missing".to_string());
+                Err(validator.problems)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Validator {
+    problems: Vec<String>,
+}
+
+impl Validator {
+    fn required(&mut self, field: &str, value: Option<&String>) -> Option<String> {
+        let value = value.filter(|value| !value.is_empty()).cloned();
+        if value.is_none() {
+            self.problems.push(format!("{field} is missing"));
+        }
+        value
+    }
+
+    /// The checked values, unless anything about the instance was wrong
+    fn finish<T>(self, values: Option<T>) -> Result<T, Vec<String>> {
+        match values {
+            Some(values) if self.problems.is_empty() => Ok(values),
+            _ => Err(self.problems),
+        }
+    }
+
+    fn url(&mut self, value: Option<&String>) -> Option<Url> {
+        let value = self.required("url", value)?;
+        match Url::parse(&value) {
+            Ok(url) if url.cannot_be_a_base() => {
+                self.problems
+                    .push(format!("url \"{value}\" cannot be used as a base url"));
+                None
+            }
+            Ok(url) => Some(url),
+            Err(err) => {
+                self.problems
+                    .push(format!("url \"{value}\" is invalid: {err}"));
+                None
+            }
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    config_arg(env::args())
+        .or_else(|| env::var(CONFIG_ENV).ok())
+        .map(PathBuf::from)
+}
+
+/// The path given as either `--config <path>` or `--config=<path>`
+fn config_arg(mut args: impl Iterator<Item = String>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == CONFIG_ARG {
+            return args.next();
+        }
+        if let Some(path) = arg
+            .strip_prefix(CONFIG_ARG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(path.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(name: &str, kind: &str, url: &str) -> InstanceConfig {
+        InstanceConfig {
+            name: Some(name.to_string()),
+            kind: Some(kind.to_string()),
+            url: Some(url.to_string()),
+            ..InstanceConfig::default()
+        }
+    }
+
+    fn problems(instance: &InstanceConfig) -> Vec<String> {
+        instance
+            .build("test")
+            .expect_err("The instance should be rejected")
+    }
+
+    #[test]
+    fn builds_valid_instance() {
+        let instance = InstanceConfig {
+            username: Some("admin".to_string()),
+            password: Some("adminadmin".to_string()),
+            ..instance("seedbox", "qbittorrent", "http://localhost:8080")
+        };
+
+        assert!(
+            instance.build("seedbox").is_ok(),
+            "A complete instance should build"
+        );
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let instance = InstanceConfig {
+            name: None,
+            ..instance("", "qbittorrent", "")
+        };
+
+        assert_eq!(
+            problems(&instance),
+            vec![
+                "name is missing",
+                "url is missing",
+                "username is missing",
+                "password is missing",
+            ],
+            "Every missing field should be reported at once"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_urls() {
+        assert_eq!(
+            problems(&instance("aria2", "aria2", "localhost:6800")),
+            vec!["url \"localhost:6800\" cannot be used as a base url"],
+            "A url without a scheme can't have paths added to it"
+        );
+        assert_eq!(
+            problems(&instance("aria2", "aria2", "http://")),
+            vec!["url \"http://\" is invalid: empty host"],
+            "An unparsable url should be reported"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_kinds() {
+        assert_eq!(
+            problems(&instance("box", "utorrent", "http://localhost")),
+            vec!["kind \"utorrent\" is not a supported backend"],
+            "Unsupported kinds should be reported"
+        );
+        assert_eq!(
+            problems(&InstanceConfig {
+                kind: None,
+                ..instance("box", "", "http://localhost")
+            }),
+            vec!["kind is missing"],
+            "A missing kind should be reported"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_env() {
+        let config = Config::from_vars(|name| match name {
+            "QBT_URL" => Some("http://localhost:8080".to_string()),
+            "QBT_USERNAME" => Some("admin".to_string()),
+            "SAB_URL" => Some("http://localhost:8081".to_string()),
+            _ => None,
+        });
+
+        let registry = config.into_registry();
+        assert_eq!(
+            registry.disabled(),
+            [
+                DisabledBackend {
+                    name: "qbittorrent".to_string(),
+                    kind: "qbittorrent".to_string(),
+                    problems: vec!["password is missing".to_string()],
+                },
+                DisabledBackend {
+                    name: "sabnzbd".to_string(),
+                    kind: "sabnzbd".to_string(),
+                    problems: vec!["api_key is missing".to_string()],
+                },
+            ],
+            "A missing SAB_API should be reported as such"
+        );
+    }
+
+    #[test]
+    fn disables_duplicate_names() {
+        let config = Config {
+            instances: vec![
+                instance("seedbox", "transmission", "http://localhost:9091"),
+                instance("seedbox", "rtorrent", "http://localhost/RPC2"),
+            ],
+        };
+
+        let registry = config.into_registry();
+        assert_eq!(
+            registry
+                .clients()
+                .map(DownloadClient::kind)
+                .collect::<Vec<_>>(),
+            vec![DownloadClientKind::Transmission],
+            "The first instance should keep its name"
+        );
+        assert_eq!(
+            registry.disabled(),
+            [DisabledBackend {
+                name: "seedbox".to_string(),
+                kind: "rtorrent".to_string(),
+                problems: vec!["Another instance is already named seedbox".to_string()],
+            }],
+            "The second instance should be disabled"
+        );
+    }
+
+    #[test]
+    fn reads_config_argument() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            config_arg(args(&["balaji", "--config", "a.toml"]).into_iter()),
+            Some("a.toml".to_string()),
+            "--config <path> should be read"
+        );
+        assert_eq!(
+            config_arg(args(&["balaji", "--config=b.toml"]).into_iter()),
+            Some("b.toml".to_string()),
+            "--config=<path> should be read"
+        );
+        assert_eq!(
+            config_arg(args(&["balaji", "--configure"]).into_iter()),
+            None,
+            "Other arguments should be ignored"
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::num::ParseFloatError;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use dioxus_fullstack::prelude::*;
+use num_traits::cast::ToPrimitive;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{non_empty, timestamp, DownloadItem};
+use crate::apis::download_item_details::{DownloadItemDetails, TorrentDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+
+const NOT_AUTHENTICATED: i64 = 1;
+const TORRENT_FIELDS: &[&str] = &[
+    "hash",
+    "name",
+    "total_size",
+    "total_done",
+    "progress",
+    "state",
+    "download_payload_rate",
+    "upload_payload_rate",
+    "eta",
+    "label",
+    "ratio",
+    "num_seeds",
+    "num_peers",
+    "tracker_host",
+    "save_path",
+    "time_added",
+    "completed_time",
+    "queue",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Item {
+    pub hash: String,
+    pub name: String,
+    pub total_size: i64,
+    pub total_done: i64,
+    pub progress: f64,
+    pub state: String,
+    pub download_payload_rate: i64,
+    pub upload_payload_rate: i64,
+    /// Seconds left, or zero when Deluge doesn't know
+    pub eta: i64,
+    /// Only sent when the Label plugin is enabled
+    #[serde(default)]
+    pub label: String,
+    /// Negative when there's no ratio yet
+    pub ratio: f64,
+    pub num_seeds: i64,
+    pub num_peers: i64,
+    pub tracker_host: String,
+    pub save_path: String,
+    pub time_added: f64,
+    /// Only sent by Deluge 2
+    #[serde(default)]
+    pub completed_time: f64,
+    /// -1 when the torrent isn't queued
+    pub queue: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcRequest<'a, T> {
+    method: &'a str,
+    params: T,
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+    code: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateUi {
+    torrents: HashMap<String, Item>,
+}
+
+#[derive(Debug)]
+pub struct Deluge {
+    name: String,
+    endpoint: Url,
+    client: Client,
+    cookies: RwLock<String>,
+    pub password: String,
+}
+
+impl Deluge {
+    pub fn new(name: String, endpoint: Url, password: String) -> Self {
+        Self {
+            name,
+            endpoint,
+            client: default_reqwest_client(),
+            cookies: RwLock::new(String::new()),
+            password,
+        }
+    }
+
+    pub(crate) async fn authenticate(&self, password: &str) -> Result<(), ServerFnError> {
+        let response = self
+            .client
+            .execute(self.rpc_request("auth.login", [password])?)
+            .await?
+            .error_for_status()?;
+        let session_id = response
+            .cookies()
+            .find(|cookie| cookie.name() == "_session_id")
+            .map(|cookie| cookie.value().to_string())
+            .unwrap_or_default();
+
+        match response.json::<RpcResponse<bool>>().await? {
+            RpcResponse {
+                result: Some(true), ..
+            } => {}
+            RpcResponse {
+                error: Some(error), ..
+            } => return Err(ServerFnError::ServerError(error.message)),
+            _ => return Err(ServerFnError::ServerError("Invalid password".to_string())),
+        }
+
+        self.cookies
+            .write()
+            .as_deref_mut()
+            .map(|x| *x = session_id)
+            .map_err(|err| ServerFnError::ServerError(err.to_string()))?;
+        Ok(())
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let initial_response = self.get_items_without_auth().await?;
+
+        let response = if is_auth_failure(&initial_response) {
+            self.authenticate(self.password.as_str()).await?;
+            self.get_items_without_auth().await?
+        } else {
+            initial_response
+        };
+
+        Ok(into_result(response)?.torrents.into_values().collect())
+    }
+
+    async fn get_items_without_auth(&self) -> Result<RpcResponse<UpdateUi>, ServerFnError> {
+        self.call(
+            "web.update_ui",
+            (TORRENT_FIELDS, HashMap::<String, String>::new()),
+        )
+        .await
+    }
+
+    async fn call<P: Serialize, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<RpcResponse<T>, ServerFnError> {
+        let request = self.rpc_request(method, params)?;
+        Ok(self
+            .client
+            .execute(request)
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    fn rpc_request<P: Serialize>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<reqwest::Request, ServerFnError> {
+        let url = self.rpc_url()?;
+        Ok(self
+            .client
+            .post(url)
+            .header(
+                reqwest::header::COOKIE,
+                format!(
+                    "_session_id={}",
+                    self.cookies
+                        .read()
+                        .map_or_else(|_| String::new(), |cookie| String::from(cookie.as_str()))
+                ),
+            )
+            .json(&RpcRequest {
+                method,
+                params,
+                id: 1,
+            })
+            .build()?)
+    }
+
+    fn rpc_url(&self) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("json");
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for Deluge {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::Deluge
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseFloatError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = self.total_size.to_u64().unwrap_or_default();
+        let done = self.total_done.to_u64().unwrap_or_default();
+
+        Ok(DownloadItem {
+            download_item_id: format!("deluge-{}", self.hash),
+            download_item_name: self.name,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: done,
+            download_bytes_left: total.saturating_sub(done),
+            download_bytes_per_second: self.download_payload_rate.to_u64(),
+            upload_bytes_per_second: self.upload_payload_rate.to_u64(),
+            download_eta: match self.eta.to_u64() {
+                None | Some(0) => Eta::Unknown,
+                Some(eta) => Eta::from_seconds(eta),
+            },
+            download_item_category: non_empty(self.label),
+            download_item_tags: vec![],
+            download_item_details: DownloadItemDetails::Torrent(TorrentDetails {
+                ratio: Some(self.ratio).filter(|ratio| *ratio >= 0.0),
+                seeds: self.num_seeds.to_u64(),
+                peers: self.num_peers.to_u64(),
+                tracker: non_empty(self.tracker_host),
+                save_path: non_empty(self.save_path),
+            }),
+            download_item_added_at: self.time_added.to_i64().and_then(timestamp),
+            download_item_completed_at: self.completed_time.to_i64().and_then(timestamp),
+            download_queue_position: self.queue.to_u64(),
+            download_percentage_complete: self.progress,
+            download_item_status: match self.state.as_str() {
+                "Downloading" | "Allocating" => DownloadStatus::Downloading,
+                "Seeding" => DownloadStatus::Seeding,
+                "Paused" if self.progress >= 100.0 => DownloadStatus::Completed,
+                "Paused" => DownloadStatus::Paused,
+                "Checking" => DownloadStatus::Checking,
+                "Queued" => DownloadStatus::Queued,
+                "Moving" => DownloadStatus::Moving,
+                "Error" => DownloadStatus::Failed,
+                _ => DownloadStatus::Unknown(self.state.clone()),
+            },
+            download_item_status_message: None,
+        })
+    }
+}
+
+fn is_auth_failure<T>(response: &RpcResponse<T>) -> bool {
+    response
+        .error
+        .as_ref()
+        .is_some_and(|error| error.code == NOT_AUTHENTICATED)
+}
+
+fn into_result<T>(response: RpcResponse<T>) -> Result<T, ServerFnError> {
+    match response {
+        RpcResponse {
+            error: Some(error), ..
+        } => Err(ServerFnError::ServerError(error.message)),
+        RpcResponse {
+            result: Some(result),
+            ..
+        } => Ok(result),
+        RpcResponse { .. } => Err(ServerFnError::ServerError(
+            "Empty response from Deluge".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "ssr")]
+use std::time::Duration;
+
+#[cfg(feature = "ssr")]
+use async_trait::async_trait;
+#[cfg(feature = "ssr")]
+use dioxus_fullstack::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+
+use crate::apis::download_item::DownloadItem;
+#[cfg(feature = "ssr")]
+use crate::apis::new_download::{NewNzb, NewTorrent, NzbPriority};
+#[cfg(feature = "ssr")]
+use crate::apis::queue_move::QueueMove;
+#[cfg(feature = "ssr")]
+use crate::apis::speed_limits::SpeedLimits;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DownloadClientKind {
+    QBittorrent,
+    Sabnzbd,
+    Transmission,
+    Deluge,
+    NzbGet,
+    RTorrent,
+    Aria2,
+    Sonarr,
+    Radarr,
+}
+
+impl Display for DownloadClientKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::QBittorrent => write!(f, "QBittorrent"),
+            Self::Sabnzbd => write!(f, "SABnzbd"),
+            Self::Transmission => write!(f, "Transmission"),
+            Self::Deluge => write!(f, "Deluge"),
+            Self::NzbGet => write!(f, "NZBGet"),
+            Self::RTorrent => write!(f, "rTorrent"),
+            Self::Aria2 => write!(f, "aria2"),
+            Self::Sonarr => write!(f, "Sonarr"),
+            Self::Radarr => write!(f, "Radarr"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    ListItems,
+    PauseResume,
+    Delete,
+    AddTorrent,
+    AddNzb,
+    Reorder,
+    SetPriority,
+    SpeedLimits,
+    /// Only for backends with [`Capability::SpeedLimits`] that limit uploads too
+    UploadLimit,
+    AlternativeSpeeds,
+    PauseAll,
+    SetCategory,
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ListItems => write!(f, "list items"),
+            Self::PauseResume => write!(f, "pause or resume items"),
+            Self::Delete => write!(f, "delete items"),
+            Self::AddTorrent => write!(f, "add torrents"),
+            Self::AddNzb => write!(f, "add NZBs"),
+            Self::Reorder => write!(f, "reorder its queue"),
+            Self::SetPriority => write!(f, "change priorities"),
+            Self::SpeedLimits => write!(f, "limit speeds"),
+            Self::UploadLimit => write!(f, "limit uploads"),
+            Self::AlternativeSpeeds => write!(f, "switch to alternative speeds"),
+            Self::PauseAll => write!(f, "pause everything"),
+            Self::SetCategory => write!(f, "change categories"),
+        }
+    }
+}
+
+/// A configured backend that could not be started, and why
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DisabledBackend {
+    pub name: String,
+    pub kind: String,
+    pub problems: Vec<String>,
+}
+
+/// An enabled backend and what it can do, for the parts of the dashboard that aren't about a
+/// single item
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BackendSummary {
+    pub name: String,
+    pub kind: DownloadClientKind,
+    pub capabilities: Vec<Capability>,
+}
+
+impl BackendSummary {
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+/// What one backend is downloading, or why we couldn't find out
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackendItems {
+    pub name: String,
+    pub kind: DownloadClientKind,
+    pub items: Result<Vec<DownloadItem>, String>,
+}
+
+/// A backend that can be asked what it is downloading
+///
+/// Implement this and register it with the [`crate::apis::Registry`] to get a new client on the
+/// dashboard. Anything past listing items is optional, a backend that can do more overrides
+/// [`DownloadClient::capabilities`] along with the methods for it.
+#[cfg(feature = "ssr")]
+#[async_trait]
+pub trait DownloadClient: Debug + Send + Sync {
+    fn name(&self) -> &str;
+
+    fn kind(&self) -> DownloadClientKind;
+
+    fn capabilities(&self) -> &'static [Capability] {
+        &[Capability::ListItems]
+    }
+
+    fn supports(&self, capability: Capability) -> bool {
+        self.capabilities().contains(&capability)
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError>;
+
+    async fn pause(&self, _download_item_id: &str) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::PauseResume))
+    }
+
+    async fn resume(&self, _download_item_id: &str) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::PauseResume))
+    }
+
+    /// Remove an item from the backend, and what it downloaded so far if `delete_files` is set
+    async fn delete(
+        &self,
+        _download_item_id: &str,
+        _delete_files: bool,
+    ) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::Delete))
+    }
+
+    async fn add_torrent(&self, _torrent: NewTorrent) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::AddTorrent))
+    }
+
+    async fn add_nzb(&self, _nzb: NewNzb) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::AddNzb))
+    }
+
+    async fn move_in_queue(
+        &self,
+        _download_item_id: &str,
+        _queue_move: QueueMove,
+    ) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::Reorder))
+    }
+
+    async fn set_priority(
+        &self,
+        _download_item_id: &str,
+        _priority: NzbPriority,
+    ) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::SetPriority))
+    }
+
+    async fn speed_limits(&self) -> Result<SpeedLimits, ServerFnError> {
+        Err(unsupported(self.kind(), Capability::SpeedLimits))
+    }
+
+    /// Limits in bytes per second, `None` to lift them. The upload limit is ignored by backends
+    /// without [`Capability::UploadLimit`].
+    async fn set_speed_limits(
+        &self,
+        _download_limit: Option<u64>,
+        _upload_limit: Option<u64>,
+    ) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::SpeedLimits))
+    }
+
+    async fn set_alternative_speeds(&self, _enabled: bool) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::AlternativeSpeeds))
+    }
+
+    /// Pause the whole backend. The [`crate::apis::Registry`] resumes it after `duration`, backends
+    /// that can resume by themselves may do so as well.
+    async fn pause_all(&self, _duration: Option<Duration>) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::PauseAll))
+    }
+
+    async fn resume_all(&self) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::PauseAll))
+    }
+
+    /// The categories an item can be put in
+    async fn categories(&self) -> Result<Vec<String>, ServerFnError> {
+        Err(unsupported(self.kind(), Capability::SetCategory))
+    }
+
+    /// `None` takes the item out of its category
+    async fn set_category(
+        &self,
+        _download_item_id: &str,
+        _category: Option<String>,
+    ) -> Result<(), ServerFnError> {
+        Err(unsupported(self.kind(), Capability::SetCategory))
+    }
+}
+
+/// What the default implementations of the optional [`DownloadClient`] methods return
+#[cfg(feature = "ssr")]
+pub fn unsupported(kind: DownloadClientKind, capability: Capability) -> ServerFnError {
+    ServerFnError::ServerError(format!("{kind} can't {capability}"))
+}
@@ -1,43 +1,124 @@
+use bytesize::ByteSize;
+#[cfg(feature = "ssr")]
+use chrono::TimeZone;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "ssr")]
+use num_traits::cast::ToPrimitive;
 use serde::{Deserialize, Serialize};
-use smooth::Smooth;
+
+use crate::apis::download_client::Capability;
+use crate::apis::download_item_details::DownloadItemDetails;
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::{human_time, Eta};
+
+#[cfg(feature = "ssr")]
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[allow(
+    clippy::struct_field_names,
+    reason = "The prefix keeps item fields apart from the download_* totals"
+)]
 pub struct DownloadItem {
     pub download_item_id: String,
     pub download_item_name: String,
-    pub download_mb_left: f64,
-    pub download_mb_total: f64,
+    /// Name of the backend the item came from, filled in by the [`crate::apis::Registry`]
+    pub download_item_backend: String,
+    /// What that backend can do with the item, also filled in by the registry
+    pub download_item_capabilities: Vec<Capability>,
+    pub download_bytes_total: u64,
+    pub download_bytes_done: u64,
+    pub download_bytes_left: u64,
+    pub download_bytes_per_second: Option<u64>,
+    pub upload_bytes_per_second: Option<u64>,
+    pub download_eta: Eta,
+    pub download_item_category: Option<String>,
+    pub download_item_tags: Vec<String>,
+    pub download_item_details: DownloadItemDetails,
+    pub download_item_added_at: Option<DateTime<Utc>>,
+    pub download_item_completed_at: Option<DateTime<Utc>>,
+    /// Where the item is in its backend's queue, 0 is the next to download. Positions are only
+    /// comparable between items of the same backend.
+    pub download_queue_position: Option<u64>,
     pub download_percentage_complete: f64,
-    pub download_item_status: String,
+    pub download_item_status: DownloadStatus,
+    pub download_item_status_message: Option<String>,
 }
 
 impl DownloadItem {
-    pub fn new(
-        download_item_id: String,
-        download_item_name: String,
-        download_mb_left: f64,
-        download_mb_total: f64,
-        download_percentage_complete: f64,
-        download_item_status: String,
-    ) -> Self {
-        Self {
-            download_item_id,
-            download_item_name,
-            download_mb_left,
-            download_mb_total,
-            download_percentage_complete,
-            download_item_status,
-        }
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.download_item_capabilities.contains(&capability)
     }
 
     pub fn human_amount_left(&self) -> String {
-        return format!("{} MB", self.download_mb_left.smooth());
+        return ByteSize::b(self.download_bytes_left).to_string();
     }
     pub fn human_amount_total(&self) -> String {
-        return format!("{} MB", self.download_mb_total.smooth());
+        return ByteSize::b(self.download_bytes_total).to_string();
+    }
+
+    pub fn human_download_rate(&self) -> Option<String> {
+        self.download_bytes_per_second
+            .map(|rate| format!("{}/s", ByteSize::b(rate)))
     }
 
-    pub fn human_percentage_complete(&self) -> String {
-        format!("{}%", self.download_percentage_complete.smooth())
+    pub fn human_upload_rate(&self) -> Option<String> {
+        self.upload_bytes_per_second
+            .map(|rate| format!("{}/s", ByteSize::b(rate)))
     }
-}
\ No newline at end of file
+
+    pub fn human_added_at(&self) -> Option<String> {
+        self.download_item_added_at.as_ref().map(human_time)
+    }
+
+    pub fn human_completed_at(&self) -> Option<String> {
+        self.download_item_completed_at.as_ref().map(human_time)
+    }
+
+    /// Counting from 1 as people do, `#1` is the next to download
+    pub fn human_queue_position(&self) -> Option<String> {
+        self.download_queue_position
+            .map(|position| format!("#{}", position + 1))
+    }
+}
+
+/// Backends use an empty string when something has no category
+#[cfg(feature = "ssr")]
+pub fn non_empty(value: String) -> Option<String> {
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// For backends that only tell us speeds in (binary) kilobytes
+#[cfg(feature = "ssr")]
+pub fn kilobytes_to_bytes(kilobytes: f64) -> u64 {
+    megabytes_to_bytes(kilobytes / 1024.0)
+}
+
+/// For backends that only tell us sizes in (binary) megabytes
+#[cfg(feature = "ssr")]
+pub fn megabytes_to_bytes(megabytes: f64) -> u64 {
+    (megabytes * BYTES_PER_MB)
+        .round()
+        .to_u64()
+        .unwrap_or_default()
+}
+
+/// Percentage of `total` that is `done`, treating an empty download as not started
+#[cfg(feature = "ssr")]
+pub fn percentage(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+
+    done.to_f64().unwrap_or_default() / total.to_f64().unwrap_or_default() * 100.0
+}
+
+/// For backends that report times as Unix timestamps, where zero or less means it hasn't happened
+#[cfg(feature = "ssr")]
+pub fn timestamp(seconds: i64) -> Option<DateTime<Utc>> {
+    if seconds <= 0 {
+        return None;
+    }
+
+    Utc.timestamp_opt(seconds, 0).single()
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use url::Url;
+
+/// Information only some kinds of backend have, kept out of [`crate::apis::DownloadItem`] so it
+/// doesn't fill up with fields that are empty for most items
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum DownloadItemDetails {
+    Torrent(TorrentDetails),
+    Usenet(UsenetDetails),
+    MediaManager(MediaManagerDetails),
+    #[default]
+    None,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct TorrentDetails {
+    pub ratio: Option<f64>,
+    pub seeds: Option<u64>,
+    pub peers: Option<u64>,
+    pub tracker: Option<String>,
+    pub save_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct UsenetDetails {
+    pub age: Option<String>,
+    pub average_age: Option<String>,
+    pub script: Option<String>,
+    pub priority: Option<String>,
+    pub password_protected: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct MediaManagerDetails {
+    pub quality: Option<String>,
+    pub protocol: Option<String>,
+    pub download_client: Option<String>,
+}
+
+impl DownloadItemDetails {
+    /// Label and value pairs for whatever this backend told us
+    pub fn human_rows(&self) -> Vec<(&'static str, String)> {
+        let rows = match self {
+            Self::Torrent(details) => vec![
+                ("Ratio", details.ratio.map(|ratio| format!("{ratio:.2}"))),
+                ("Seeds", details.seeds.map(|seeds| seeds.to_string())),
+                ("Peers", details.peers.map(|peers| peers.to_string())),
+                ("Tracker", details.tracker.clone()),
+                ("Save path", details.save_path.clone()),
+            ],
+            Self::Usenet(details) => vec![
+                ("Age", details.age.clone()),
+                ("Average age", details.average_age.clone()),
+                ("Script", details.script.clone()),
+                ("Priority", details.priority.clone()),
+                (
+                    "Password protected",
+                    details
+                        .password_protected
+                        .map(|protected| if protected { "Yes" } else { "No" }.to_string()),
+                ),
+            ],
+            Self::MediaManager(details) => vec![
+                ("Quality", details.quality.clone()),
+                ("Protocol", details.protocol.clone()),
+                ("Download client", details.download_client.clone()),
+            ],
+            Self::None => vec![],
+        };
+
+        rows.into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect()
+    }
+}
+
+/// Trackers are shown by host, the full announce URL often has a passkey in it
+#[cfg(feature = "ssr")]
+pub fn tracker_host(announce: &str) -> Option<String> {
+    Url::parse(announce)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+}
@@ -0,0 +1,36 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Where a download is up to, in terms every backend can be mapped into
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub enum DownloadStatus {
+    Downloading,
+    Queued,
+    Paused,
+    Stalled,
+    Checking,
+    Seeding,
+    Completed,
+    Failed,
+    Moving,
+    /// A status we don't recognise, as the backend reported it
+    Unknown(String),
+}
+
+impl Display for DownloadStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Downloading => write!(f, "Downloading"),
+            Self::Queued => write!(f, "Queued"),
+            Self::Paused => write!(f, "Paused"),
+            Self::Stalled => write!(f, "Stalled"),
+            Self::Checking => write!(f, "Checking"),
+            Self::Seeding => write!(f, "Seeding"),
+            Self::Completed => write!(f, "Completed"),
+            Self::Failed => write!(f, "Failed"),
+            Self::Moving => write!(f, "Moving"),
+            Self::Unknown(raw) => write!(f, "Unknown ({raw})"),
+        }
+    }
+}
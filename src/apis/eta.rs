@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// How long until a download finishes
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Eta {
+    Remaining(Duration),
+    /// The backend can't say, usually because the download is paused, stalled or queued
+    Unknown,
+}
+
+impl Eta {
+    #[cfg(feature = "ssr")]
+    pub const fn from_seconds(seconds: u64) -> Self {
+        Self::Remaining(Duration::from_secs(seconds))
+    }
+
+    /// Work the ETA out ourselves for backends that only tell us the speed
+    #[cfg(feature = "ssr")]
+    pub const fn from_rate(bytes_left: u64, bytes_per_second: Option<u64>) -> Self {
+        match bytes_per_second {
+            Some(rate) if rate > 0 => Self::from_seconds(bytes_left.div_ceil(rate)),
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Parse the `hours:minutes:seconds` time left that `SABnzbd` and the *arrs report, which may
+    /// be prefixed by `days:` or `days.` and followed by fractional seconds
+    ///
+    /// A zero time left is what a paused queue reports, so it is treated as unknown.
+    #[cfg(feature = "ssr")]
+    pub fn from_timeleft(timeleft: &str) -> Self {
+        let (days, clock) = match timeleft.split_once('.') {
+            // Only a dot before the hours separates the days
+            Some((days, clock)) if !days.contains(':') => (Some(days), clock),
+            _ => (None, timeleft),
+        };
+        let clock = clock.split_once('.').map_or(clock, |(clock, _)| clock);
+
+        let Ok(parts) = days
+            .into_iter()
+            .chain(clock.split(':'))
+            .map(str::parse::<u64>)
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return Self::Unknown;
+        };
+
+        let seconds = parts
+            .iter()
+            .rev()
+            .zip([1, 60, 3_600, 86_400])
+            .map(|(part, unit)| part * unit)
+            .sum::<u64>();
+
+        match (parts.len(), seconds) {
+            (_, 0) | (5.., _) => Self::Unknown,
+            _ => Self::from_seconds(seconds),
+        }
+    }
+
+    /// A countdown such as `1h 23m`
+    pub fn human_remaining(&self) -> String {
+        let Self::Remaining(remaining) = self else {
+            return "unknown".to_string();
+        };
+
+        let seconds = remaining.as_secs();
+        let (days, hours, minutes, seconds) = (
+            seconds / 86_400,
+            seconds / 3_600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        );
+
+        match (days, hours, minutes) {
+            (0, 0, 0) => format!("{seconds}s"),
+            (0, 0, _) => format!("{minutes}m {seconds:02}s"),
+            (0, _, _) => format!("{hours}h {minutes:02}m"),
+            _ => format!("{days}d {hours}h"),
+        }
+    }
+
+    /// The local time it should be done by
+    pub fn human_done_at(&self) -> Option<String> {
+        let Self::Remaining(remaining) = self else {
+            return None;
+        };
+
+        Some(human_time(
+            &(Local::now() + chrono::Duration::from_std(*remaining).ok()?),
+        ))
+    }
+}
+
+/// A local time such as `14:05`, with the day if it isn't today
+pub fn human_time<Tz: TimeZone>(at: &DateTime<Tz>) -> String {
+    let at = at.with_timezone(&Local);
+
+    if at.date_naive() == Local::now().date_naive() {
+        at.format("%H:%M").to_string()
+    } else {
+        at.format("%a %-d %b %H:%M").to_string()
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timeleft() {
+        assert_eq!(
+            Eta::from_timeleft("0:05:12"),
+            Eta::from_seconds(312),
+            "hours:minutes:seconds"
+        );
+        assert_eq!(
+            Eta::from_timeleft("1:02:03:04"),
+            Eta::from_seconds(93_784),
+            "days:hours:minutes:seconds"
+        );
+        assert_eq!(
+            Eta::from_timeleft("1.02:03:04"),
+            Eta::from_seconds(93_784),
+            "days.hours:minutes:seconds"
+        );
+        assert_eq!(
+            Eta::from_timeleft("00:05:12.3456789"),
+            Eta::from_seconds(312),
+            "Fractional seconds should be dropped"
+        );
+        assert_eq!(
+            Eta::from_timeleft("1.02:03:04.5"),
+            Eta::from_seconds(93_784),
+            "Days and fractional seconds together"
+        );
+    }
+
+    #[test]
+    fn unknown_timeleft() {
+        assert_eq!(
+            Eta::from_timeleft("0:00:00"),
+            Eta::Unknown,
+            "A paused queue's zero should be unknown"
+        );
+        assert_eq!(Eta::from_timeleft(""), Eta::Unknown, "Empty");
+        assert_eq!(Eta::from_timeleft("soon"), Eta::Unknown, "Not a time");
+        assert_eq!(
+            Eta::from_timeleft("1:1:02:03:04"),
+            Eta::Unknown,
+            "Too many parts"
+        );
+    }
+
+    #[test]
+    fn works_out_rate() {
+        assert_eq!(
+            Eta::from_rate(1_000, Some(300)),
+            Eta::from_seconds(4),
+            "Partial seconds should round up"
+        );
+        assert_eq!(Eta::from_rate(1_000, Some(0)), Eta::Unknown, "Stalled");
+        assert_eq!(Eta::from_rate(1_000, None), Eta::Unknown, "No rate");
+    }
+}
@@ -1,79 +1,183 @@
-use std::env;
-use std::fmt::Display;
+#![allow(
+    clippy::unsafe_derive_deserialize,
+    reason = "#[server] derives Deserialize for the arguments of every server function"
+)]
 
+use std::time::Duration;
+
+#[cfg(feature = "ssr")]
 use dioxus_fullstack::once_cell::sync::Lazy;
 use dioxus_fullstack::prelude::*;
-use url::Url;
+#[cfg(feature = "ssr")]
+use log::info;
 
-use crate::apis::download_item::DownloadItem;
 #[cfg(feature = "ssr")]
-use crate::apis::qbittorrent::QBitTorrent;
+use crate::apis::config::Config;
+use crate::apis::download_client::{BackendItems, BackendSummary, DisabledBackend};
+use crate::apis::new_download::{NewNzb, NewTorrent, NzbPriority};
+use crate::apis::pause_state::PauseState;
+use crate::apis::queue_move::QueueMove;
 #[cfg(feature = "ssr")]
-use crate::apis::sabnzbd::Sabnzbd;
+use crate::apis::registry::Registry;
+use crate::apis::speed_limits::SpeedLimits;
 
 #[cfg(feature = "ssr")]
-static SABNZBD: Lazy<Sabnzbd> = Lazy::new(|| {
-    let endpoint = env::var("SAB_URL").and_then(Url::parse).expect("Invalid SAB url");
-    Sabnzbd::new(
-        endpoint,
-        env::var("SAB_API").expect("Invalid SAB url").to_string(),
-    )
-});
+static REGISTRY: Lazy<Registry> = Lazy::new(Config::load_registry);
 
+/// Load and validate the configuration up front, so problems show in the logs at start up
 #[cfg(feature = "ssr")]
-static QBT: Lazy<QBitTorrent> = Lazy::new(|| {
-    let endpoint = env::var("QBT_URL").and_then(Url::parse).expect("Invalid QBT url");
-    let username = env::var("QBT_USERNAME").expect("Invalid QBT username");
-    let password = env::var("QBT_PASSWORD").expect("Invalid QBT password");
+pub fn init() {
+    Lazy::force(&REGISTRY).report();
+}
 
-    QBitTorrent::new(endpoint, username, password)
-});
+#[server]
+pub async fn get_download_items() -> Result<Vec<BackendItems>, ServerFnError> {
+    Ok(REGISTRY.list_items().await)
+}
 
 #[server]
-pub async fn get_sab_items() -> Result<Vec<DownloadItem>, ServerFnError> {
-    info!("Getting items from Sabnzbd");
-    SABNZBD
-        .get_items()
+pub async fn get_backends() -> Result<Vec<BackendSummary>, ServerFnError> {
+    Ok(REGISTRY.summaries())
+}
+
+#[server]
+pub async fn pause_download_item(
+    backend: String,
+    download_item_id: String,
+) -> Result<(), ServerFnError> {
+    info!("Pausing {download_item_id} on {backend}");
+    REGISTRY.client(&backend)?.pause(&download_item_id).await
+}
+
+#[server]
+pub async fn resume_download_item(
+    backend: String,
+    download_item_id: String,
+) -> Result<(), ServerFnError> {
+    info!("Resuming {download_item_id} on {backend}");
+    REGISTRY.client(&backend)?.resume(&download_item_id).await
+}
+
+#[server]
+pub async fn delete_download_item(
+    backend: String,
+    download_item_id: String,
+    delete_files: bool,
+) -> Result<(), ServerFnError> {
+    info!("Deleting {download_item_id} on {backend}, with files: {delete_files}");
+    REGISTRY
+        .client(&backend)?
+        .delete(&download_item_id, delete_files)
+        .await
+}
+
+/// Sent as CBOR, as an uploaded `.torrent` file would be needlessly large url-encoded
+#[server(AddTorrent, "/api", "Cbor")]
+pub async fn add_torrent(backend: String, torrent: NewTorrent) -> Result<(), ServerFnError> {
+    info!("Adding a torrent to {backend}");
+    REGISTRY.client(&backend)?.add_torrent(torrent).await
+}
+
+/// CBOR for the same reason as [`add_torrent`]
+#[server(AddNzb, "/api", "Cbor")]
+pub async fn add_nzb(backend: String, nzb: NewNzb) -> Result<(), ServerFnError> {
+    info!("Adding an NZB to {backend}");
+    REGISTRY.client(&backend)?.add_nzb(nzb).await
+}
+
+/// CBOR as url-encoding can't carry a [`QueueMove::To`]
+#[server(MoveDownloadItem, "/api", "Cbor")]
+pub async fn move_download_item(
+    backend: String,
+    download_item_id: String,
+    queue_move: QueueMove,
+) -> Result<(), ServerFnError> {
+    info!("Moving {download_item_id} on {backend}: {queue_move:?}");
+    REGISTRY
+        .client(&backend)?
+        .move_in_queue(&download_item_id, queue_move)
         .await
-        .map_err(|err| {
-            warn!("Error getting items from Sabnzbd: {}", err);
-            err
-        })?
-        .into_iter()
-        .map(TryInto::<DownloadItem>::try_into)
-        .collect::<Result<Vec<DownloadItem>, _>>()
-        .map_err(|err| {
-            warn!("Error converting items from Sabnzbd: {}", err);
-            ServerFnError::from(err)
-        })
-        .map(|value| {
-            info!("Got items {} from Sabnzbd", value.len());
-            value
-        })
 }
 
 #[server]
-pub async fn get_qbt_items() -> Result<Vec<DownloadItem>, ServerFnError> {
-    info!("Getting items from QBittorrent");
-    QBT.get_items()
+pub async fn set_download_item_priority(
+    backend: String,
+    download_item_id: String,
+    priority: NzbPriority,
+) -> Result<(), ServerFnError> {
+    info!("Setting the priority of {download_item_id} on {backend} to {priority}");
+    REGISTRY
+        .client(&backend)?
+        .set_priority(&download_item_id, priority)
         .await
-        .map_err(|err| {
-            warn!("Error getting items from QBittorrent: {}", err);
-            err
-        })?
-        .into_iter()
-        .map(TryInto::<DownloadItem>::try_into)
-        .collect::<Result<Vec<DownloadItem>, _>>()
-        .map_err(|err| {
-            warn!("Error getting items from QBittorrent: {}", err);
-            ServerFnError::from(err)
-        })
-        .map(|value| {
-            info!("Got items {} from QBittorrent", value.len());
-            value
-        })
-}
-
-fn to_server_err<T: Display>(x: T) -> ServerFnError {
-    ServerFnError::ServerError(format!("{x}"))
+}
+
+#[server]
+pub async fn get_speed_limits(backend: String) -> Result<SpeedLimits, ServerFnError> {
+    REGISTRY.client(&backend)?.speed_limits().await
+}
+
+/// CBOR as url-encoding can't tell an unlimited `None` from a missing argument
+#[server(SetSpeedLimits, "/api", "Cbor")]
+pub async fn set_speed_limits(
+    backend: String,
+    download_limit: Option<u64>,
+    upload_limit: Option<u64>,
+) -> Result<(), ServerFnError> {
+    info!("Limiting {backend} to {download_limit:?} down and {upload_limit:?} up");
+    REGISTRY
+        .client(&backend)?
+        .set_speed_limits(download_limit, upload_limit)
+        .await
+}
+
+#[server]
+pub async fn set_alternative_speeds(backend: String, enabled: bool) -> Result<(), ServerFnError> {
+    info!("Setting alternative speeds on {backend} to {enabled}");
+    REGISTRY
+        .client(&backend)?
+        .set_alternative_speeds(enabled)
+        .await
+}
+
+#[server]
+pub async fn get_pause_state() -> Result<PauseState, ServerFnError> {
+    Ok(REGISTRY.pause_state())
+}
+
+/// CBOR as url-encoding can't carry a [`Duration`]
+#[server(PauseAll, "/api", "Cbor")]
+pub async fn pause_all(duration: Option<Duration>) -> Result<(), ServerFnError> {
+    info!("Pausing everything for {duration:?}");
+    REGISTRY.pause_all(duration).await
+}
+
+#[server]
+pub async fn resume_all() -> Result<(), ServerFnError> {
+    info!("Resuming everything");
+    REGISTRY.resume_all().await
+}
+
+#[server]
+pub async fn get_categories(backend: String) -> Result<Vec<String>, ServerFnError> {
+    REGISTRY.client(&backend)?.categories().await
+}
+
+/// CBOR as url-encoding can't tell a `None` category from a missing argument
+#[server(SetDownloadItemCategory, "/api", "Cbor")]
+pub async fn set_download_item_category(
+    backend: String,
+    download_item_id: String,
+    category: Option<String>,
+) -> Result<(), ServerFnError> {
+    info!("Setting the category of {download_item_id} on {backend} to {category:?}");
+    REGISTRY
+        .client(&backend)?
+        .set_category(&download_item_id, category)
+        .await
+}
+
+#[server]
+pub async fn get_disabled_backends() -> Result<Vec<DisabledBackend>, ServerFnError> {
+    Ok(REGISTRY.disabled().to_vec())
 }
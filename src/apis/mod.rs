@@ -1,15 +1,44 @@
+#[cfg(feature = "ssr")]
+mod aria2;
+#[cfg(feature = "ssr")]
+mod arr;
+#[cfg(feature = "ssr")]
+mod config;
+#[cfg(feature = "ssr")]
+mod deluge;
+mod download_client;
 mod download_item;
+mod download_item_details;
+mod download_status;
+mod eta;
 mod facade;
+mod new_download;
+#[cfg(feature = "ssr")]
+mod nzbget;
+mod pause_state;
 #[cfg(feature = "ssr")]
 mod qbittorrent;
+mod queue_move;
+#[cfg(feature = "ssr")]
+mod registry;
 #[cfg(feature = "ssr")]
 mod reqwest;
 #[cfg(feature = "ssr")]
+mod rtorrent;
+#[cfg(feature = "ssr")]
 mod sabnzbd;
+mod speed_limits;
+#[cfg(feature = "ssr")]
+mod transmission;
+#[cfg(feature = "ssr")]
+mod xmlrpc;
 
+pub use download_client::{BackendItems, BackendSummary, Capability};
 pub use download_item::DownloadItem;
+pub use download_item_details::DownloadItemDetails;
+pub use download_status::DownloadStatus;
+pub use eta::Eta;
 pub use facade::*;
-#[cfg(feature = "ssr")]
-pub use qbittorrent::QBitTorrent;
-#[cfg(feature = "ssr")]
-pub use sabnzbd::Sabnzbd;
+pub use new_download::{DownloadSource, NewNzb, NewTorrent, NzbPriority, PostProcessing};
+pub use pause_state::PauseState;
+pub use queue_move::QueueMove;
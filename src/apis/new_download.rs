@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Where a new download comes from
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum DownloadSource {
+    /// Links for the backend to fetch itself, one per line. For torrents these may be magnet
+    /// links.
+    Urls(String),
+    /// A file uploaded through the dashboard
+    File {
+        file_name: String,
+        contents: Vec<u8>,
+    },
+}
+
+/// A torrent to add, with the options every torrent backend has in some form
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct NewTorrent {
+    pub source: DownloadSource,
+    /// The backend's default when not set
+    pub category: Option<String>,
+    /// The backend's default when not set
+    pub save_path: Option<String>,
+    /// Add it without starting it
+    pub paused: bool,
+}
+
+/// An NZB to add, with the options usenet backends have
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct NewNzb {
+    pub source: DownloadSource,
+    /// The backend's default when not set, as are the rest
+    pub category: Option<String>,
+    pub priority: Option<NzbPriority>,
+    pub post_processing: Option<PostProcessing>,
+    /// Name of the script to run once it's done
+    pub script: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NzbPriority {
+    /// Added to the queue paused
+    Paused,
+    Low,
+    Normal,
+    High,
+    /// Downloaded straight away, even when the queue is paused
+    Force,
+}
+
+impl NzbPriority {
+    pub const ALL: [Self; 5] = [
+        Self::Paused,
+        Self::Low,
+        Self::Normal,
+        Self::High,
+        Self::Force,
+    ];
+}
+
+impl Display for NzbPriority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Paused => write!(f, "Paused"),
+            Self::Low => write!(f, "Low"),
+            Self::Normal => write!(f, "Normal"),
+            Self::High => write!(f, "High"),
+            Self::Force => write!(f, "Force"),
+        }
+    }
+}
+
+/// How much is done once the download finishes, each step includes the ones before it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PostProcessing {
+    None,
+    Repair,
+    Unpack,
+    /// Delete the downloaded files once they're unpacked
+    Delete,
+}
+
+impl PostProcessing {
+    pub const ALL: [Self; 4] = [Self::None, Self::Repair, Self::Unpack, Self::Delete];
+}
+
+impl Display for PostProcessing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "Download only"),
+            Self::Repair => write!(f, "Repair"),
+            Self::Unpack => write!(f, "Repair and unpack"),
+            Self::Delete => write!(f, "Repair, unpack and delete"),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::num::ParseFloatError;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use dioxus_fullstack::prelude::ServerFnError;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{non_empty, percentage, DownloadItem};
+use crate::apis::download_item_details::{DownloadItemDetails, UsenetDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Item {
+    #[serde(rename = "NZBID")]
+    pub nzb_id: i64,
+    #[serde(rename = "NZBName")]
+    pub nzb_name: String,
+    pub file_size_lo: u32,
+    pub file_size_hi: u32,
+    pub remaining_size_lo: u32,
+    pub remaining_size_hi: u32,
+    pub status: String,
+    pub category: String,
+    pub max_priority: i64,
+    /// Unix timestamp of the oldest article
+    pub min_post_time: i64,
+    /// `NZBGet` only reports the speed of the whole server, this is that speed for the group being
+    /// downloaded and zero for the rest
+    #[serde(skip)]
+    pub bytes_per_second: u64,
+}
+
+impl Item {
+    /// `NZBGet` splits sizes into two 32 bit halves for the benefit of JSON parsers without 64 bit
+    /// integers
+    fn join_halves(lo: u32, hi: u32) -> u64 {
+        (u64::from(hi) << 32) | u64::from(lo)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Status {
+    pub download_paused: bool,
+    pub download_rate: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcRequest<'a, T> {
+    method: &'a str,
+    params: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Debug)]
+pub struct NzbGet {
+    name: String,
+    endpoint: Url,
+    client: Client,
+    pub username: String,
+    pub password: String,
+}
+
+impl NzbGet {
+    pub(crate) fn new(name: String, endpoint: Url, username: String, password: String) -> Self {
+        Self {
+            name,
+            endpoint,
+            client: default_reqwest_client(),
+            username,
+            password,
+        }
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let (items, status) = futures_util::future::try_join(
+            self.call::<Vec<Item>>("listgroups", [0]),
+            self.call::<Status>("status", Vec::<i64>::new()),
+        )
+        .await?;
+
+        // A paused queue leaves groups reporting QUEUED, so make it obvious they won't move
+        let mut items = items
+            .into_iter()
+            .map(|mut item| {
+                if status.download_paused && item.status == "QUEUED" {
+                    item.status = "PAUSED".to_string();
+                }
+                item
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(item) = items.iter_mut().find(|item| item.status == "DOWNLOADING") {
+            item.bytes_per_second = status.download_rate;
+        }
+        Ok(items)
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: impl Serialize + Send,
+    ) -> Result<T, ServerFnError> {
+        let response = self
+            .client
+            .post(self.rpc_url()?)
+            .basic_auth(&self.username, Some(&self.password))
+            .json(&RpcRequest { method, params })
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RpcResponse<T>>()
+            .await?;
+
+        match response {
+            RpcResponse {
+                error: Some(error), ..
+            } => Err(ServerFnError::ServerError(error.message)),
+            RpcResponse {
+                result: Some(result),
+                ..
+            } => Ok(result),
+            RpcResponse { .. } => Err(ServerFnError::ServerError(format!(
+                "Empty response from NZBGet for {method}"
+            ))),
+        }
+    }
+
+    fn rpc_url(&self) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("jsonrpc");
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for NzbGet {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::NzbGet
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseFloatError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = Self::join_halves(self.file_size_lo, self.file_size_hi);
+        let left = Self::join_halves(self.remaining_size_lo, self.remaining_size_hi);
+
+        Ok(DownloadItem {
+            download_item_id: format!("nzbget-{}", self.nzb_id),
+            download_item_name: self.nzb_name,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: total.saturating_sub(left),
+            download_bytes_left: left,
+            download_bytes_per_second: Some(self.bytes_per_second),
+            upload_bytes_per_second: None,
+            download_eta: Eta::from_rate(left, Some(self.bytes_per_second)),
+            download_item_category: non_empty(self.category),
+            download_item_tags: vec![],
+            download_item_details: DownloadItemDetails::Usenet(UsenetDetails {
+                age: Some(format!(
+                    "{}d",
+                    (Utc::now().timestamp() - self.min_post_time).max(0) / 86_400
+                )),
+                average_age: None,
+                script: None,
+                priority: Some(
+                    match self.max_priority {
+                        900.. => "Force",
+                        100.. => "Very High",
+                        50.. => "High",
+                        0.. => "Normal",
+                        -50.. => "Low",
+                        _ => "Very Low",
+                    }
+                    .to_string(),
+                ),
+                password_protected: None,
+            }),
+            download_item_added_at: None,
+            download_item_completed_at: None,
+            download_queue_position: None,
+            download_percentage_complete: percentage(total.saturating_sub(left), total),
+            download_item_status: match self.status.as_str() {
+                "DOWNLOADING" | "FETCHING" => DownloadStatus::Downloading,
+                "QUEUED" | "PP_QUEUED" => DownloadStatus::Queued,
+                "PAUSED" => DownloadStatus::Paused,
+                "LOADING_PARS" | "VERIFYING_SOURCES" | "REPAIRING" | "VERIFYING_REPAIRED" => {
+                    DownloadStatus::Checking
+                }
+                "RENAMING" | "UNPACKING" | "MOVING" | "EXECUTING_SCRIPT" => DownloadStatus::Moving,
+                "PP_FINISHED" => DownloadStatus::Completed,
+                _ => DownloadStatus::Unknown(self.status.clone()),
+            },
+            download_item_status_message: None,
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::apis::eta::human_time;
+
+/// Whether everything was paused from the dashboard
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused {
+        /// When everything is resumed again, `None` to stay paused until someone resumes it
+        resume_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl PauseState {
+    pub const fn is_paused(&self) -> bool {
+        matches!(self, Self::Paused { .. })
+    }
+
+    /// Such as `Paused until 14:30`
+    pub fn human(&self) -> String {
+        match self {
+            Self::Running => "Running".to_string(),
+            Self::Paused { resume_at: None } => "Paused".to_string(),
+            Self::Paused {
+                resume_at: Some(resume_at),
+            } => format!("Paused until {}", human_time(resume_at)),
+        }
+    }
+}
@@ -1,27 +1,60 @@
+use std::collections::HashMap;
 use std::num::ParseFloatError;
 use std::sync::RwLock;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use dioxus_fullstack::prelude::*;
+use futures_util::future::try_join3;
 use num_traits::cast::ToPrimitive;
-use reqwest::{Client, Response};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::IgnoredAny;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::apis::download_item::DownloadItem;
+use crate::apis::download_client::{Capability, DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{non_empty, timestamp, DownloadItem};
+use crate::apis::download_item_details::{tracker_host, DownloadItemDetails, TorrentDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::new_download::{DownloadSource, NewTorrent};
+use crate::apis::queue_move::QueueMove;
 use crate::apis::reqwest::default_reqwest_client;
+use crate::apis::speed_limits::SpeedLimits;
+
+/// What qBittorrent reports as the ETA when it has no idea
+const INFINITE_ETA: i64 = 8_640_000;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
     pub size: i64,
     pub downloaded: i64,
+    pub amount_left: i64,
     pub hash: String,
     pub progress: f64,
     pub state: String,
+    pub dlspeed: i64,
+    pub upspeed: i64,
+    pub eta: i64,
+    pub category: String,
+    /// Comma separated
+    pub tags: String,
+    pub ratio: f64,
+    pub num_seeds: i64,
+    pub num_leechs: i64,
+    pub tracker: String,
+    pub save_path: String,
+    pub added_on: i64,
+    pub completion_on: i64,
+    /// Position in the queue starting at 1, or 0 when queueing is disabled or it's not queued
+    pub priority: i64,
 }
 
 #[derive(Debug)]
 pub struct QBitTorrent {
+    name: String,
     endpoint: Url,
     client: Client,
     cookies: RwLock<String>,
@@ -30,8 +63,9 @@ pub struct QBitTorrent {
 }
 
 impl QBitTorrent {
-    pub fn new(endpoint: Url, username: String, password: String) -> Self {
+    pub fn new(name: String, endpoint: Url, username: String, password: String) -> Self {
         Self {
+            name,
             endpoint,
             client: default_reqwest_client(),
             cookies: RwLock::new(String::new()),
@@ -57,8 +91,7 @@ impl QBitTorrent {
             .header(
                 reqwest::header::HOST,
                 url.host()
-                    .map(|h| h.to_string())
-                    .unwrap_or_else(|| "localhost".to_string()),
+                    .map_or_else(|| "localhost".to_string(), |h| h.to_string()),
             )
             .form(&[("username", username), ("password", password)])
             .build()?;
@@ -80,7 +113,7 @@ impl QBitTorrent {
     fn authenticate_url(&self) -> Result<Url, ServerFnError> {
         let mut url = self.endpoint.clone();
         url.path_segments_mut()
-            .map_err(|_| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
             .push("api")
             .push("v2")
             .push("auth")
@@ -110,19 +143,9 @@ impl QBitTorrent {
             .header(
                 reqwest::header::HOST,
                 url.host()
-                    .map(|h| h.to_string())
-                    .unwrap_or_else(|| "localhost".to_string()),
-            )
-            .header(
-                reqwest::header::COOKIE,
-                format!(
-                    "SID={}",
-                    match self.cookies.read() {
-                        Ok(cookie) => String::from(cookie.as_str()),
-                        _ => String::new(),
-                    }
-                ),
+                    .map_or_else(|| "localhost".to_string(), |h| h.to_string()),
             )
+            .header(reqwest::header::COOKIE, self.sid_cookie())
             .build()?;
         let response = self.client.execute(request).await?;
         Ok(response)
@@ -131,7 +154,7 @@ impl QBitTorrent {
     fn get_items_url(&self) -> Result<Url, ServerFnError> {
         let mut call = self.endpoint.clone();
         call.path_segments_mut()
-            .map_err(|_| ServerFnError::ServerError("Invalid base url".to_string()))? // Only can happen with an invalid base url
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))? // Only can happen with an invalid base url
             .push("api")
             .push("v2")
             .push("torrents")
@@ -141,6 +164,331 @@ impl QBitTorrent {
             .finish();
         Ok(call)
     }
+
+    /// The hash qBittorrent knows a torrent by, from the id we gave its item
+    fn hash(download_item_id: &str) -> Result<&str, ServerFnError> {
+        download_item_id
+            .strip_prefix("qbittorrent-")
+            .ok_or_else(|| {
+                ServerFnError::ServerError(format!("{download_item_id} isn't a qBittorrent item"))
+            })
+    }
+
+    fn api_url(&self, segments: &[&str]) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("api")
+            .push("v2")
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn sid_cookie(&self) -> String {
+        format!(
+            "SID={}",
+            self.cookies
+                .read()
+                .map_or_else(|_| String::new(), |cookie| String::from(cookie.as_str()))
+        )
+    }
+
+    /// POST a form to the API, logging in again first if the session has expired
+    async fn post_form(
+        &self,
+        segments: &[&str],
+        form: &[(&str, &str)],
+    ) -> Result<Response, ServerFnError> {
+        let initial_response = self.post_form_without_auth(segments, form).await?;
+
+        if is_auth_failure(&initial_response) {
+            self.authenticate(self.username.as_str(), self.password.as_str())
+                .await?;
+            self.post_form_without_auth(segments, form).await
+        } else {
+            Ok(initial_response)
+        }
+    }
+
+    async fn post_form_without_auth(
+        &self,
+        segments: &[&str],
+        form: &[(&str, &str)],
+    ) -> Result<Response, ServerFnError> {
+        let request = self
+            .client
+            .post(self.api_url(segments)?)
+            .header(reqwest::header::COOKIE, self.sid_cookie())
+            .form(form)
+            .build()?;
+        Ok(self.client.execute(request).await?)
+    }
+
+    async fn post_multipart_without_auth(
+        &self,
+        segments: &[&str],
+        form: Form,
+    ) -> Result<Response, ServerFnError> {
+        let request = self
+            .client
+            .post(self.api_url(segments)?)
+            .header(reqwest::header::COOKIE, self.sid_cookie())
+            .multipart(form)
+            .build()?;
+        Ok(self.client.execute(request).await?)
+    }
+
+    /// The form for `torrents/add`, a function as a multipart form can't be sent twice when the
+    /// first attempt needs to log in again
+    fn add_torrent_form(torrent: &NewTorrent) -> Result<Form, ServerFnError> {
+        let paused = if torrent.paused { "true" } else { "false" };
+        let form = Form::new()
+            .text("paused", paused)
+            // qBittorrent 4 reads paused and 5 reads stopped, each ignores the other's field
+            .text("stopped", paused);
+        let form = match &torrent.source {
+            DownloadSource::Urls(urls) => form.text("urls", urls.clone()),
+            DownloadSource::File {
+                file_name,
+                contents,
+            } => form.part(
+                "torrents",
+                Part::bytes(contents.clone())
+                    .file_name(file_name.clone())
+                    .mime_str("application/x-bittorrent")?,
+            ),
+        };
+        let form = match &torrent.category {
+            Some(category) => form.text("category", category.clone()),
+            None => form,
+        };
+        let form = match &torrent.save_path {
+            Some(save_path) => form.text("savepath", save_path.clone()),
+            None => form,
+        };
+        Ok(form)
+    }
+
+    /// Run a torrent action qBittorrent 5 renamed, such as `pause` to `stop`, trying the new name
+    /// when the old one is gone
+    async fn post_renamed_action(
+        &self,
+        old: &str,
+        new: &str,
+        form: &[(&str, &str)],
+    ) -> Result<(), ServerFnError> {
+        let response = self.post_form(&["torrents", old], form).await?;
+        let response = if response.status() == StatusCode::NOT_FOUND {
+            self.post_form(&["torrents", new], form).await?
+        } else {
+            response
+        };
+
+        response.error_for_status()?;
+        Ok(())
+    }
+
+    /// One of the `transfer` endpoints that answer with a number, 0 meaning unlimited or off
+    async fn transfer_value(&self, endpoint: &str) -> Result<u64, ServerFnError> {
+        let value = self
+            .post_form(&["transfer", endpoint], &[])
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        value.trim().parse::<u64>().map_err(ServerFnError::from)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for QBitTorrent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::QBittorrent
+    }
+
+    fn capabilities(&self) -> &'static [Capability] {
+        &[
+            Capability::ListItems,
+            Capability::PauseResume,
+            Capability::Delete,
+            Capability::AddTorrent,
+            Capability::Reorder,
+            Capability::SpeedLimits,
+            Capability::UploadLimit,
+            Capability::AlternativeSpeeds,
+            Capability::PauseAll,
+            Capability::SetCategory,
+        ]
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+
+    async fn pause(&self, download_item_id: &str) -> Result<(), ServerFnError> {
+        let form = [("hashes", Self::hash(download_item_id)?)];
+        self.post_renamed_action("pause", "stop", &form).await
+    }
+
+    async fn resume(&self, download_item_id: &str) -> Result<(), ServerFnError> {
+        let form = [("hashes", Self::hash(download_item_id)?)];
+        self.post_renamed_action("resume", "start", &form).await
+    }
+
+    async fn delete(
+        &self,
+        download_item_id: &str,
+        delete_files: bool,
+    ) -> Result<(), ServerFnError> {
+        let form = [
+            ("hashes", Self::hash(download_item_id)?),
+            ("deleteFiles", if delete_files { "true" } else { "false" }),
+        ];
+        self.post_form(&["torrents", "delete"], &form)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn add_torrent(&self, torrent: NewTorrent) -> Result<(), ServerFnError> {
+        let segments = ["torrents", "add"];
+        let initial_response = self
+            .post_multipart_without_auth(&segments, Self::add_torrent_form(&torrent)?)
+            .await?;
+        let response = if is_auth_failure(&initial_response) {
+            self.authenticate(self.username.as_str(), self.password.as_str())
+                .await?;
+            self.post_multipart_without_auth(&segments, Self::add_torrent_form(&torrent)?)
+                .await?
+        } else {
+            initial_response
+        };
+
+        // Older versions answer a torrent they couldn't add with a 200 and this body
+        match response.error_for_status()?.text().await?.as_str() {
+            "Fails." => Err(ServerFnError::ServerError(
+                "qBittorrent couldn't add the torrent".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    async fn move_in_queue(
+        &self,
+        download_item_id: &str,
+        queue_move: QueueMove,
+    ) -> Result<(), ServerFnError> {
+        let form = [("hashes", Self::hash(download_item_id)?)];
+        let (action, steps) = match queue_move {
+            QueueMove::Top | QueueMove::To { to: 0, .. } => ("topPrio", 1),
+            QueueMove::Bottom => ("bottomPrio", 1),
+            QueueMove::To { from, to } if to < from => ("increasePrio", from - to),
+            QueueMove::To { from, to } => ("decreasePrio", to - from),
+        };
+
+        // Other than to the top or bottom, qBittorrent only moves a torrent one place at a time
+        for _ in 0..steps {
+            self.post_form(&["torrents", action], &form)
+                .await?
+                .error_for_status()?;
+        }
+        Ok(())
+    }
+
+    /// The limits in use, which are the alternative ones while those are on
+    async fn speed_limits(&self) -> Result<SpeedLimits, ServerFnError> {
+        let (download_limit, upload_limit, speed_limits_mode) = try_join3(
+            self.transfer_value("downloadLimit"),
+            self.transfer_value("uploadLimit"),
+            self.transfer_value("speedLimitsMode"),
+        )
+        .await?;
+
+        Ok(SpeedLimits {
+            download_limit: Some(download_limit).filter(|limit| *limit > 0),
+            upload_limit: Some(upload_limit).filter(|limit| *limit > 0),
+            alternative_speeds: Some(speed_limits_mode == 1),
+        })
+    }
+
+    async fn set_speed_limits(
+        &self,
+        download_limit: Option<u64>,
+        upload_limit: Option<u64>,
+    ) -> Result<(), ServerFnError> {
+        let download_limit = download_limit.unwrap_or_default().to_string();
+        let upload_limit = upload_limit.unwrap_or_default().to_string();
+        self.post_form(
+            &["transfer", "setDownloadLimit"],
+            &[("limit", &download_limit)],
+        )
+        .await?
+        .error_for_status()?;
+        self.post_form(&["transfer", "setUploadLimit"], &[("limit", &upload_limit)])
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn set_alternative_speeds(&self, enabled: bool) -> Result<(), ServerFnError> {
+        // There's only a toggle, so check first to not turn them off by asking for them on
+        if (self.transfer_value("speedLimitsMode").await? == 1) != enabled {
+            self.post_form(&["transfer", "toggleSpeedLimitsMode"], &[])
+                .await?
+                .error_for_status()?;
+        }
+        Ok(())
+    }
+
+    /// qBittorrent can't resume by itself, that's left to the registry's timer
+    async fn pause_all(&self, _duration: Option<Duration>) -> Result<(), ServerFnError> {
+        self.post_renamed_action("pause", "stop", &[("hashes", "all")])
+            .await
+    }
+
+    async fn resume_all(&self) -> Result<(), ServerFnError> {
+        self.post_renamed_action("resume", "start", &[("hashes", "all")])
+            .await
+    }
+
+    async fn categories(&self) -> Result<Vec<String>, ServerFnError> {
+        // Keyed by name, only the names are wanted
+        let categories = self
+            .post_form(&["torrents", "categories"], &[])
+            .await?
+            .error_for_status()?
+            .json::<HashMap<String, IgnoredAny>>()
+            .await?;
+
+        let mut names = categories.into_keys().collect::<Vec<_>>();
+        names.sort_unstable();
+        Ok(names)
+    }
+
+    async fn set_category(
+        &self,
+        download_item_id: &str,
+        category: Option<String>,
+    ) -> Result<(), ServerFnError> {
+        let form = [
+            ("hashes", Self::hash(download_item_id)?),
+            // An empty category takes the torrent out of its category
+            ("category", category.as_deref().unwrap_or_default()),
+        ];
+        self.post_form(&["torrents", "setCategory"], &form)
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
 
 impl TryInto<DownloadItem> for Item {
@@ -150,37 +498,82 @@ impl TryInto<DownloadItem> for Item {
         Ok(DownloadItem {
             download_item_id: format!("qbittorrent-{}", self.hash),
             download_item_name: self.name,
-            download_mb_left: (self.size.to_f64().unwrap_or_default()
-                + self.downloaded.to_f64().unwrap_or_default())
-                / 1024.0
-                / 1024.0,
-            download_mb_total: (self.size.to_f64().unwrap_or_default()) / 1024.0 / 1024.0,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: self.size.to_u64().unwrap_or_default(),
+            download_bytes_done: (self.size - self.amount_left).to_u64().unwrap_or_default(),
+            download_bytes_left: self.amount_left.to_u64().unwrap_or_default(),
+            download_bytes_per_second: self.dlspeed.to_u64(),
+            upload_bytes_per_second: self.upspeed.to_u64(),
+            download_eta: eta(self.eta),
+            download_item_category: non_empty(self.category),
+            download_item_tags: self
+                .tags
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(ToString::to_string)
+                .collect(),
+            download_item_details: DownloadItemDetails::Torrent(TorrentDetails {
+                ratio: Some(self.ratio),
+                seeds: self.num_seeds.to_u64(),
+                peers: self.num_leechs.to_u64(),
+                tracker: tracker_host(&self.tracker),
+                save_path: non_empty(self.save_path),
+            }),
+            download_item_added_at: timestamp(self.added_on),
+            download_item_completed_at: timestamp(self.completion_on),
+            download_queue_position: (self.priority - 1).to_u64(),
             download_percentage_complete: self.progress * 100.0,
             download_item_status: match self.state.as_str() {
-                "error" => "Some error occurred, applies to paused torrents",
-                "missingFiles" => "Torrent data files is missing",
-                "uploading" => "Torrent is being seeded and data is being transferred",
-                "pausedUP" => "Torrent is paused and has finished downloading",
-                "queuedUP" => "Queuing is enabled and torrent is queued for upload",
-                "stalledUP" => "Torrent is being seeded, but no connection were made",
-                "checkingUP" => "Torrent has finished downloading and is being checked",
-                "forcedUP" => "Torrent is forced to uploading and ignore queue limit",
-                "allocating" => "Torrent is allocating disk space for download",
-                "downloading" => "Torrent is being downloaded and data is being transferred",
-                "metaDL" => "Torrent has just started downloading and is fetching metadata",
-                "pausedDL" => "Torrent is paused and has NOT finished downloading",
-                "queuedDL" => "Queuing is enabled and torrent is queued for download",
-                "stalledDL" => "Torrent is being downloaded, but no connection were made",
-                "checkingDL" => "Same as checkingUP, but torrent has NOT finished downloading",
-                "forcedDL" => "Torrent is forced to downloading to ignore queue limit",
-                "checkingResumeData" => "Checking resume data on qBt startup",
-                "moving" => "Torrent is moving to another location",
-                _ => "Unknown status",
-            }.to_string(),
+                "error" | "missingFiles" => DownloadStatus::Failed,
+                "uploading" | "forcedUP" | "stalledUP" => DownloadStatus::Seeding,
+                "pausedUP" | "stoppedUP" => DownloadStatus::Completed,
+                "queuedUP" | "queuedDL" => DownloadStatus::Queued,
+                "checkingUP" | "checkingDL" | "checkingResumeData" => DownloadStatus::Checking,
+                "allocating" | "downloading" | "metaDL" | "forcedDL" | "forcedMetaDL" => {
+                    DownloadStatus::Downloading
+                }
+                "pausedDL" | "stoppedDL" => DownloadStatus::Paused,
+                "stalledDL" => DownloadStatus::Stalled,
+                "moving" => DownloadStatus::Moving,
+                _ => DownloadStatus::Unknown(self.state.clone()),
+            },
+            download_item_status_message: match self.state.as_str() {
+                "error" => Some("Some error occurred, applies to paused torrents".to_string()),
+                "missingFiles" => Some("Torrent data files is missing".to_string()),
+                _ => None,
+            },
         })
     }
 }
 
+fn eta(seconds: i64) -> Eta {
+    match seconds {
+        INFINITE_ETA => Eta::Unknown,
+        seconds => seconds.to_u64().map_or(Eta::Unknown, Eta::from_seconds),
+    }
+}
+
 fn is_auth_failure(response: &reqwest::Response) -> bool {
-    response.status() == reqwest::StatusCode::UNAUTHORIZED
+    matches!(
+        response.status(),
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_eta() {
+        assert_eq!(eta(90), Eta::from_seconds(90), "Seconds should be kept");
+        assert_eq!(
+            eta(INFINITE_ETA),
+            Eta::Unknown,
+            "The infinite sentinel should be unknown"
+        );
+        assert_eq!(eta(-1), Eta::Unknown, "Negative seconds should be unknown");
+    }
 }
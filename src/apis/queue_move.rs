@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Where to move an item in its backend's queue
+///
+/// Positions are [`crate::apis::DownloadItem::download_queue_position`]s, so start at 0.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum QueueMove {
+    Top,
+    Bottom,
+    /// Also used for a single step up or down. Backends that only move items a step at a time
+    /// need to know where it is now.
+    To {
+        from: u64,
+        to: u64,
+    },
+}
+
+impl QueueMove {
+    /// One place closer to being downloaded, `None` when it's already next
+    pub fn up(position: u64) -> Option<Self> {
+        Some(Self::To {
+            from: position,
+            to: position.checked_sub(1)?,
+        })
+    }
+
+    pub const fn down(position: u64) -> Self {
+        Self::To {
+            from: position,
+            to: position + 1,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use chrono::Utc;
+use dioxus_fullstack::prelude::ServerFnError;
+use futures_util::future::join_all;
+use log::{error, info, warn};
+
+use crate::apis::download_client::{
+    BackendItems, BackendSummary, Capability, DisabledBackend, DownloadClient,
+};
+use crate::apis::download_item::DownloadItem;
+use crate::apis::pause_state::PauseState;
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    clients: Vec<Box<dyn DownloadClient>>,
+    disabled: Vec<DisabledBackend>,
+    pause_state: Mutex<PauseState>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, client: Box<dyn DownloadClient>) {
+        self.clients.push(client);
+    }
+
+    pub fn disable(&mut self, backend: DisabledBackend) {
+        self.disabled.push(backend);
+    }
+
+    pub fn disabled(&self) -> &[DisabledBackend] {
+        &self.disabled
+    }
+
+    /// Log what was enabled, and every problem with what wasn't
+    pub fn report(&self) {
+        for client in self.clients() {
+            info!("Enabled {} backend {}", client.kind(), client.name());
+        }
+
+        for backend in &self.disabled {
+            for problem in &backend.problems {
+                error!(
+                    "Disabled {} backend {}: {}",
+                    backend.kind, backend.name, problem
+                );
+            }
+        }
+
+        if self.clients.is_empty() {
+            warn!("No backends are enabled, the dashboard will be empty");
+        }
+    }
+
+    pub fn clients(&self) -> impl Iterator<Item = &dyn DownloadClient> {
+        self.clients.iter().map(AsRef::as_ref)
+    }
+
+    pub fn summaries(&self) -> Vec<BackendSummary> {
+        self.clients()
+            .map(|client| BackendSummary {
+                name: client.name().to_string(),
+                kind: client.kind(),
+                capabilities: client.capabilities().to_vec(),
+            })
+            .collect()
+    }
+
+    /// The client an item came from, by the name in [`DownloadItem::download_item_backend`]
+    pub fn client(&self, name: &str) -> Result<&dyn DownloadClient, ServerFnError> {
+        self.clients()
+            .find(|client| client.name() == name)
+            .ok_or_else(|| ServerFnError::ServerError(format!("No backend named {name}")))
+    }
+
+    /// Ask every client for its items at once
+    ///
+    /// A failing client doesn't fail the others, its error is returned in its place.
+    pub async fn list_items(&self) -> Vec<BackendItems> {
+        join_all(self.clients().map(|client| async move {
+            info!("Getting items from {}", client.name());
+            let items = client
+                .list_items()
+                .await
+                .map_err(|err| {
+                    warn!("Error getting items from {}: {}", client.name(), err);
+                    err.to_string()
+                })
+                .map(|value| {
+                    info!("Got items {} from {}", value.len(), client.name());
+                    value
+                        .into_iter()
+                        .map(|item| DownloadItem {
+                            download_item_backend: client.name().to_string(),
+                            download_item_capabilities: client.capabilities().to_vec(),
+                            ..item
+                        })
+                        .collect()
+                });
+
+            BackendItems {
+                name: client.name().to_string(),
+                kind: client.kind(),
+                items,
+            }
+        }))
+        .await
+    }
+
+    pub fn pause_state(&self) -> PauseState {
+        *self.lock_pause_state()
+    }
+
+    fn lock_pause_state(&self) -> MutexGuard<'_, PauseState> {
+        // A plain value can't be left half written, so a poisoned lock is still fine to use
+        self.pause_state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pause every backend that can be, resuming them again after `duration` if there is one
+    ///
+    /// Pausing again or resuming before then cancels the timer.
+    pub async fn pause_all(&'static self, duration: Option<Duration>) -> Result<(), ServerFnError> {
+        let resume_at = duration
+            .and_then(|duration| chrono::Duration::from_std(duration).ok())
+            .map(|duration| Utc::now() + duration);
+        let results = self
+            .for_each_client(Capability::PauseAll, |client| client.pause_all(duration))
+            .await;
+
+        // Nothing was paused, so there's nothing to show or to resume later
+        if !results.iter().any(Result::is_ok) {
+            return combine(results);
+        }
+
+        let paused = PauseState::Paused { resume_at };
+        *self.lock_pause_state() = paused;
+
+        if let Some(duration) = duration {
+            tokio::spawn(async move {
+                tokio::time::sleep(duration).await;
+                if self.pause_state() != paused {
+                    return;
+                }
+                info!("Resuming everything after {duration:?}");
+                if let Err(err) = self.resume_all().await {
+                    warn!("Error resuming everything: {err}");
+                }
+            });
+        }
+
+        combine(results)
+    }
+
+    pub async fn resume_all(&self) -> Result<(), ServerFnError> {
+        let results = self
+            .for_each_client(Capability::PauseAll, DownloadClient::resume_all)
+            .await;
+
+        // Everything is still paused if nothing could be resumed
+        if results.iter().any(Result::is_ok) {
+            *self.lock_pause_state() = PauseState::Running;
+        }
+
+        combine(results)
+    }
+
+    /// Run `action` on every client that has `capability` at once, with what every client that
+    /// failed said
+    async fn for_each_client<'a, F, Fut>(
+        &'a self,
+        capability: Capability,
+        action: F,
+    ) -> Vec<Result<(), String>>
+    where
+        F: Fn(&'a dyn DownloadClient) -> Fut + Sync,
+        Fut: Future<Output = Result<(), ServerFnError>> + Send,
+    {
+        let action = &action;
+        join_all(
+            self.clients()
+                .filter(|client| client.supports(capability))
+                .map(|client| async move {
+                    action(client)
+                        .await
+                        .map_err(|err| format!("{}: {err}", client.name()))
+                }),
+        )
+        .await
+    }
+}
+
+/// Fail with every problem in `results`, if there are any
+fn combine(results: Vec<Result<(), String>>) -> Result<(), ServerFnError> {
+    let problems = results
+        .into_iter()
+        .filter_map(Result::err)
+        .collect::<Vec<_>>();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ServerFnError::ServerError(problems.join("; ")))
+    }
+}
@@ -0,0 +1,427 @@
+use std::num::ParseFloatError;
+
+use async_trait::async_trait;
+use dioxus_fullstack::prelude::ServerFnError;
+use num_traits::cast::ToPrimitive;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{non_empty, percentage, timestamp, DownloadItem};
+use crate::apis::download_item_details::{DownloadItemDetails, TorrentDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+use crate::apis::xmlrpc::{method_call, parse_response, Value};
+
+/// Columns asked for by `d.multicall2`, in the order [`Item::from_row`] expects them
+const TORRENT_COMMANDS: &[&str] = &[
+    "d.hash=",
+    "d.name=",
+    "d.size_bytes=",
+    "d.completed_bytes=",
+    "d.state=",
+    "d.is_active=",
+    "d.complete=",
+    "d.hashing=",
+    "d.message=",
+    "d.down.rate=",
+    "d.up.rate=",
+    "d.custom1=",
+    "d.ratio=",
+    "d.peers_complete=",
+    "d.peers_accounted=",
+    "d.directory=",
+    "d.load_date=",
+    "d.timestamp.finished=",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Item {
+    pub hash: String,
+    pub name: String,
+    pub size_bytes: i64,
+    pub completed_bytes: i64,
+    pub state: i64,
+    pub is_active: i64,
+    pub complete: i64,
+    pub hashing: i64,
+    pub message: String,
+    pub down_rate: i64,
+    pub up_rate: i64,
+    /// Where ruTorrent keeps its label
+    pub custom1: String,
+    /// Thousandths, 1500 is a ratio of 1.5
+    pub ratio: i64,
+    pub peers_complete: i64,
+    pub peers_accounted: i64,
+    pub directory: String,
+    pub load_date: i64,
+    pub timestamp_finished: i64,
+}
+
+impl Item {
+    fn from_row(row: Value) -> Option<Self> {
+        let row = row.into_array()?;
+        let text = |index: usize| {
+            row.get(index)
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+        };
+        let number = |index: usize| row.get(index).and_then(Value::as_i64);
+
+        Some(Self {
+            hash: text(0)?,
+            name: text(1)?,
+            size_bytes: number(2)?,
+            completed_bytes: number(3)?,
+            state: number(4)?,
+            is_active: number(5)?,
+            complete: number(6)?,
+            hashing: number(7)?,
+            message: text(8).unwrap_or_default(),
+            down_rate: number(9)?,
+            up_rate: number(10)?,
+            custom1: text(11).unwrap_or_default(),
+            ratio: number(12)?,
+            peers_complete: number(13)?,
+            peers_accounted: number(14)?,
+            directory: text(15).unwrap_or_default(),
+            load_date: number(16).unwrap_or_default(),
+            timestamp_finished: number(17).unwrap_or_default(),
+        })
+    }
+}
+
+/// rTorrent over its XML-RPC interface
+///
+/// rTorrent only speaks SCGI itself, so `endpoint` is the full URL of the HTTP proxy in front of
+/// it, usually `/RPC2` on the nginx serving ruTorrent.
+#[derive(Debug)]
+pub struct RTorrent {
+    name: String,
+    endpoint: Url,
+    client: Client,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl RTorrent {
+    pub fn new(
+        name: String,
+        endpoint: Url,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Self {
+        Self {
+            name,
+            endpoint,
+            client: default_reqwest_client(),
+            username,
+            password,
+        }
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let params = [&["", "main"][..], TORRENT_COMMANDS].concat();
+        let rows = self
+            .call("d.multicall2", &params)
+            .await?
+            .into_array()
+            .ok_or_else(|| ServerFnError::ServerError("Expected a list of torrents".to_string()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                Item::from_row(row).ok_or_else(|| {
+                    ServerFnError::ServerError("Unexpected torrent from rTorrent".to_string())
+                })
+            })
+            .collect()
+    }
+
+    async fn call(&self, method: &str, params: &[&str]) -> Result<Value, ServerFnError> {
+        let mut request = self
+            .client
+            .post(self.endpoint.clone())
+            .header(reqwest::header::CONTENT_TYPE, "text/xml")
+            .body(method_call(method, params));
+
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+
+        let body = request.send().await?.error_for_status()?.text().await?;
+        parse_response(&body).map_err(ServerFnError::ServerError)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for RTorrent {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::RTorrent
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseFloatError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = self.size_bytes.to_u64().unwrap_or_default();
+        let done = self.completed_bytes.to_u64().unwrap_or_default();
+
+        Ok(DownloadItem {
+            download_item_id: format!("rtorrent-{}", self.hash),
+            download_item_name: self.name,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: done,
+            download_bytes_left: total.saturating_sub(done),
+            download_bytes_per_second: self.down_rate.to_u64(),
+            upload_bytes_per_second: self.up_rate.to_u64(),
+            download_eta: Eta::from_rate(total.saturating_sub(done), self.down_rate.to_u64()),
+            download_item_category: non_empty(self.custom1),
+            download_item_tags: vec![],
+            download_item_details: DownloadItemDetails::Torrent(TorrentDetails {
+                ratio: self.ratio.to_f64().map(|ratio| ratio / 1000.0),
+                seeds: self.peers_complete.to_u64(),
+                peers: self.peers_accounted.to_u64(),
+                tracker: None,
+                save_path: non_empty(self.directory),
+            }),
+            download_item_added_at: timestamp(self.load_date),
+            download_item_completed_at: timestamp(self.timestamp_finished),
+            download_queue_position: None,
+            download_percentage_complete: percentage(done, total),
+            download_item_status: match (self.state, self.is_active, self.complete, self.hashing) {
+                (_, _, _, hashing) if hashing != 0 => DownloadStatus::Checking,
+                (0, _, 1, _) | (_, 0, 1, _) => DownloadStatus::Completed,
+                (0, _, _, _) | (_, 0, _, _) => DownloadStatus::Paused,
+                (_, _, 1, _) => DownloadStatus::Seeding,
+                _ => DownloadStatus::Downloading,
+            },
+            download_item_status_message: Some(self.message).filter(|message| !message.is_empty()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Five torrents as `d.multicall2` returns them for [`TORRENT_COMMANDS`]: downloading,
+    /// seeding with a tracker warning, paused, stopped after finishing and being checked
+    const MULTICALL_RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<methodResponse>
+<params>
+<param><value><array><data>
+<value><array><data>
+<value><string>5A8CE26E8A19A877D8CCC927FCC18E34E1F5FF67</string></value>
+<value><string>ubuntu-22.04.3-desktop-amd64.iso</string></value>
+<value><i8>5037662208</i8></value>
+<value><i8>2518831104</i8></value>
+<value><i8>1</i8></value>
+<value><i8>1</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string></string></value>
+<value><i8>1048576</i8></value>
+<value><i8>65536</i8></value>
+<value><string>linux</string></value>
+<value><i8>250</i8></value>
+<value><i8>12</i8></value>
+<value><i8>40</i8></value>
+<value><string>/downloads/ubuntu-22.04.3-desktop-amd64.iso</string></value>
+<value><i8>1697620000</i8></value>
+<value><i8>0</i8></value>
+</data></array></value>
+<value><array><data>
+<value><string>0A1B2C3D4E5F60718293A4B5C6D7E8F901234567</string></value>
+<value><string>debian-12.2.0-amd64-netinst.iso</string></value>
+<value><i8>658505728</i8></value>
+<value><i8>658505728</i8></value>
+<value><i8>1</i8></value>
+<value><i8>1</i8></value>
+<value><i8>1</i8></value>
+<value><i8>0</i8></value>
+<value><string>Tracker: [Failure reason &quot;unregistered torrent&quot;]</string></value>
+<value><i8>0</i8></value>
+<value><i8>2048</i8></value>
+<value><string/></value>
+<value><i8>1500</i8></value>
+<value><i8>0</i8></value>
+<value><i8>3</i8></value>
+<value><string>/downloads</string></value>
+<value><i8>1697610000</i8></value>
+<value><i8>1697615000</i8></value>
+</data></array></value>
+<value><array><data>
+<value><string>1111111111111111111111111111111111111111</string></value>
+<value><string>paused</string></value>
+<value><i8>100</i8></value>
+<value><i8>50</i8></value>
+<value><i8>1</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+</data></array></value>
+<value><array><data>
+<value><string>2222222222222222222222222222222222222222</string></value>
+<value><string>finished</string></value>
+<value><i8>100</i8></value>
+<value><i8>100</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><i8>1</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+</data></array></value>
+<value><array><data>
+<value><string>3333333333333333333333333333333333333333</string></value>
+<value><string>checking</string></value>
+<value><i8>100</i8></value>
+<value><i8>0</i8></value>
+<value><i8>1</i8></value>
+<value><i8>1</i8></value>
+<value><i8>0</i8></value>
+<value><i8>1</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+<value><string/></value>
+<value><i8>0</i8></value>
+<value><i8>0</i8></value>
+</data></array></value>
+</data></array></value></param>
+</params>
+</methodResponse>"#;
+
+    fn rows() -> Vec<Value> {
+        parse_response(MULTICALL_RESPONSE)
+            .expect("Response should parse")
+            .into_array()
+            .expect("Response should be a list of torrents")
+    }
+
+    fn download_items() -> Vec<DownloadItem> {
+        rows()
+            .into_iter()
+            .map(|row| {
+                let item = Item::from_row(row).expect("Every row should be a torrent");
+                TryInto::<DownloadItem>::try_into(item).expect("Every torrent should convert")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_every_column() {
+        let row = rows()
+            .into_iter()
+            .next()
+            .expect("There should be a torrent");
+        let item = Item::from_row(row).expect("The row should be a torrent");
+        assert_eq!(
+            item.hash, "5A8CE26E8A19A877D8CCC927FCC18E34E1F5FF67",
+            "hash"
+        );
+        assert_eq!(item.name, "ubuntu-22.04.3-desktop-amd64.iso", "name");
+        assert_eq!(item.size_bytes, 5_037_662_208, "size");
+        assert_eq!(item.completed_bytes, 2_518_831_104, "completed bytes");
+        assert_eq!(
+            (item.state, item.is_active, item.complete, item.hashing),
+            (1, 1, 0, 0),
+            "state"
+        );
+        assert_eq!(item.message, "", "message");
+        assert_eq!((item.down_rate, item.up_rate), (1_048_576, 65536), "rates");
+        assert_eq!(item.custom1, "linux", "label");
+        assert_eq!(item.ratio, 250, "ratio");
+        assert_eq!(
+            (item.peers_complete, item.peers_accounted),
+            (12, 40),
+            "peers"
+        );
+        assert_eq!(
+            item.directory, "/downloads/ubuntu-22.04.3-desktop-amd64.iso",
+            "directory"
+        );
+        assert_eq!(
+            (item.load_date, item.timestamp_finished),
+            (1_697_620_000, 0),
+            "timestamps"
+        );
+    }
+
+    #[test]
+    fn maps_state_to_status() {
+        let statuses = download_items()
+            .into_iter()
+            .map(|item| item.download_item_status)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statuses,
+            vec![
+                DownloadStatus::Downloading,
+                DownloadStatus::Seeding,
+                DownloadStatus::Paused,
+                DownloadStatus::Completed,
+                DownloadStatus::Checking,
+            ],
+            "Statuses should follow state, active, complete and hashing"
+        );
+    }
+
+    #[test]
+    fn keeps_tracker_message_informational() {
+        let items = download_items();
+        let seeding = items.get(1).expect("There should be a second torrent");
+
+        assert_eq!(
+            seeding.download_item_status_message.as_deref(),
+            Some("Tracker: [Failure reason \"unregistered torrent\"]"),
+            "The tracker message should be kept as the status message"
+        );
+        assert_eq!(
+            seeding.download_item_category, None,
+            "An empty label should be no category"
+        );
+    }
+}
@@ -1,13 +1,25 @@
+use async_trait::async_trait;
 use dioxus_fullstack::prelude::ServerFnError;
+use futures_util::future::try_join_all;
+use reqwest::multipart::{Form, Part};
+use num_traits::cast::ToPrimitive;
 use reqwest::Client;
 use std::num::ParseFloatError;
 use std::str::FromStr;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::apis::download_item::DownloadItem;
+use crate::apis::download_client::{Capability, DownloadClient, DownloadClientKind};
+use crate::apis::download_item_details::{DownloadItemDetails, UsenetDetails};
+use crate::apis::download_item::{kilobytes_to_bytes, megabytes_to_bytes, timestamp, DownloadItem};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::new_download::{DownloadSource, NewNzb, NzbPriority, PostProcessing};
+use crate::apis::queue_move::QueueMove;
 use crate::apis::reqwest::default_reqwest_client;
+use crate::apis::speed_limits::SpeedLimits;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Item {
@@ -17,6 +29,27 @@ pub struct Item {
     pub nzo_id: String,
     pub percentage: String,
     pub status: String,
+    pub timeleft: String,
+    /// `*` when the slot is in the default category
+    pub cat: String,
+    pub avg_age: String,
+    /// `None` when there's no post-processing script
+    pub script: String,
+    pub priority: String,
+    /// Older versions leave this out
+    #[serde(default)]
+    pub password: Option<String>,
+    pub index: i64,
+    /// Older versions leave this out
+    #[serde(default)]
+    pub time_added: Option<i64>,
+    /// `SABnzbd` only reports the speed of the whole queue, this is that speed for the first slot
+    /// being downloaded and zero for the rest
+    ///
+    /// When no slot is being downloaded, such as between one finishing and the next starting, the
+    /// queue speed is left out rather than shown on an item it doesn't belong to.
+    #[serde(skip)]
+    pub bytes_per_second: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,45 +57,83 @@ struct Response {
     queue: Queue,
 }
 
+/// What the modes that change something answer with when they fail, and most of them when they
+/// don't
+#[derive(Debug, Serialize, Deserialize)]
+struct Status {
+    #[serde(default)]
+    status: Option<bool>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Categories {
+    /// Includes `*`, the default category
+    categories: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Queue {
+    kbpersec: String,
+    /// Bytes per second, empty when there's no limit
+    #[serde(default)]
+    speedlimit_abs: String,
     slots: Vec<Item>,
 }
 
 #[derive(Debug)]
 pub struct Sabnzbd {
+    name: String,
     endpoint: Url,
     apikey: String,
     client: Client,
 }
 
 impl Sabnzbd {
-    pub(crate) fn new(endpoint: Url, apikey: String) -> Self {
+    pub(crate) fn new(name: String, endpoint: Url, apikey: String) -> Self {
         Self {
+            name,
             endpoint,
             apikey,
             client: default_reqwest_client(),
         }
     }
 
-    fn authenticated_url(&self) -> Url {
+    fn authenticated_url(&self) -> Result<Url, ServerFnError> {
         let mut url = self.endpoint.clone();
-        url.path_segments_mut().expect("Invalid path").push("api"); // should never fail, checked in new.
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("api");
         url.query_pairs_mut()
             .append_pair("apikey", &self.apikey)
             .append_pair("output", "json")
             .finish();
-        url
+        Ok(url)
     }
 
     pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let queue = self.get_queue().await?;
+        let bytes_per_second = kilobytes_to_bytes(f64::from_str(&queue.kbpersec)?);
+
+        let mut slots = queue.slots;
+        if let Some(slot) = slots
+            .iter_mut()
+            .find(|slot| matches!(slot.status.as_str(), "Downloading" | "Fetching" | "Grabbing"))
+        {
+            slot.bytes_per_second = bytes_per_second;
+        }
+        Ok(slots)
+    }
+
+    async fn get_queue(&self) -> Result<Queue, ServerFnError> {
         let call = self
-            .authenticated_url()
+            .authenticated_url()?
             .query_pairs_mut()
             .append_pair("mode", "queue")
             .finish()
             .to_string();
-        Ok(self
+        let queue = self
             .client
             .get(call)
             .send()
@@ -70,8 +141,296 @@ impl Sabnzbd {
             .error_for_status()?
             .json::<Response>()
             .await?
-            .queue
-            .slots)
+            .queue;
+        Ok(queue)
+    }
+
+    /// Call a mode that changes something, checking whether it worked
+    async fn call(&self, params: &[(&str, &str)]) -> Result<(), ServerFnError> {
+        let mut call = self.authenticated_url()?;
+        call.query_pairs_mut().extend_pairs(params);
+        Self::check_status(self.client.get(call).send().await?).await
+    }
+
+    /// [`Sabnzbd::call`] for `mode=addfile`, which wants the file in a POST body as `name`
+    async fn call_with_file(
+        &self,
+        params: &[(&str, &str)],
+        file_name: &str,
+        contents: &[u8],
+    ) -> Result<(), ServerFnError> {
+        let mut call = self.authenticated_url()?;
+        call.query_pairs_mut().extend_pairs(params);
+        let form = Form::new().part(
+            "name",
+            Part::bytes(contents.to_vec())
+                .file_name(file_name.to_string())
+                .mime_str("application/x-nzb")?,
+        );
+        Self::check_status(self.client.post(call).multipart(form).send().await?).await
+    }
+
+    async fn check_status(response: reqwest::Response) -> Result<(), ServerFnError> {
+        let status = response.error_for_status()?.json::<Status>().await?;
+
+        match status {
+            Status {
+                status: Some(false),
+                error,
+            } => Err(ServerFnError::ServerError(
+                error.unwrap_or_else(|| "SABnzbd refused the request".to_string()),
+            )),
+            Status { .. } => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl DownloadClient for Sabnzbd {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::Sabnzbd
+    }
+
+    fn capabilities(&self) -> &'static [Capability] {
+        &[
+            Capability::ListItems,
+            Capability::PauseResume,
+            Capability::Delete,
+            Capability::AddNzb,
+            Capability::Reorder,
+            Capability::SetPriority,
+            Capability::SpeedLimits,
+            Capability::PauseAll,
+            Capability::SetCategory,
+        ]
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+
+    async fn pause(&self, download_item_id: &str) -> Result<(), ServerFnError> {
+        self.call(&[
+            ("mode", "queue"),
+            ("name", "pause"),
+            ("value", download_item_id),
+        ])
+        .await
+    }
+
+    async fn resume(&self, download_item_id: &str) -> Result<(), ServerFnError> {
+        self.call(&[
+            ("mode", "queue"),
+            ("name", "resume"),
+            ("value", download_item_id),
+        ])
+        .await
+    }
+
+    async fn delete(
+        &self,
+        download_item_id: &str,
+        delete_files: bool,
+    ) -> Result<(), ServerFnError> {
+        self.call(&[
+            ("mode", "queue"),
+            ("name", "delete"),
+            ("value", download_item_id),
+            ("del_files", if delete_files { "1" } else { "0" }),
+        ])
+        .await
+    }
+
+    async fn add_nzb(&self, nzb: NewNzb) -> Result<(), ServerFnError> {
+        let priority = nzb.priority.map(priority_value);
+        let post_processing = nzb.post_processing.map(|post_processing| match post_processing {
+            PostProcessing::None => "0",
+            PostProcessing::Repair => "1",
+            PostProcessing::Unpack => "2",
+            PostProcessing::Delete => "3",
+        });
+        let options = [
+            ("cat", nzb.category.as_deref()),
+            ("priority", priority),
+            ("pp", post_processing),
+            ("script", nzb.script.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect::<Vec<_>>();
+
+        match &nzb.source {
+            DownloadSource::File {
+                file_name,
+                contents,
+            } => {
+                let params = [&[("mode", "addfile")][..], &options].concat();
+                self.call_with_file(&params, file_name, contents).await
+            }
+            DownloadSource::Urls(urls) => {
+                let urls = urls
+                    .lines()
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .collect::<Vec<_>>();
+                if urls.is_empty() {
+                    return Err(ServerFnError::ServerError(
+                        "No NZB links were given".to_string(),
+                    ));
+                }
+
+                try_join_all(urls.into_iter().map(|url| {
+                    let params = [&[("mode", "addurl"), ("name", url)][..], &options].concat();
+                    async move { self.call(&params).await }
+                }))
+                .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// `SABnzbd` can only switch to an index, so moving to the bottom reads the queue's length
+    /// first. Anything added in between ends up below the item.
+    async fn move_in_queue(
+        &self,
+        download_item_id: &str,
+        queue_move: QueueMove,
+    ) -> Result<(), ServerFnError> {
+        let position = match queue_move {
+            QueueMove::Top => "0".to_string(),
+            QueueMove::Bottom => self
+                .get_queue()
+                .await?
+                .slots
+                .len()
+                .saturating_sub(1)
+                .to_string(),
+            QueueMove::To { to, .. } => to.to_string(),
+        };
+        self.call(&[
+            ("mode", "switch"),
+            ("value", download_item_id),
+            ("value2", &position),
+        ])
+        .await
+    }
+
+    async fn set_priority(
+        &self,
+        download_item_id: &str,
+        priority: NzbPriority,
+    ) -> Result<(), ServerFnError> {
+        self.call(&[
+            ("mode", "queue"),
+            ("name", "priority"),
+            ("value", download_item_id),
+            ("value2", priority_value(priority)),
+        ])
+        .await
+    }
+
+    async fn speed_limits(&self) -> Result<SpeedLimits, ServerFnError> {
+        let download_limit = match self.get_queue().await?.speedlimit_abs.as_str() {
+            "" => None,
+            limit => f64::from_str(limit)?.round().to_u64().filter(|limit| *limit > 0),
+        };
+
+        Ok(SpeedLimits {
+            download_limit,
+            upload_limit: None,
+            alternative_speeds: None,
+        })
+    }
+
+    async fn set_speed_limits(
+        &self,
+        download_limit: Option<u64>,
+        _upload_limit: Option<u64>,
+    ) -> Result<(), ServerFnError> {
+        // Without a unit the value would be a percentage of the configured maximum speed
+        let value = download_limit.map_or_else(
+            || "0".to_string(),
+            |limit| format!("{}K", limit.div_ceil(1024)),
+        );
+        self.call(&[("mode", "config"), ("name", "speedlimit"), ("value", &value)])
+            .await
+    }
+
+    /// `SABnzbd` is told the duration too, so it still resumes if the dashboard is restarted before
+    /// then
+    async fn pause_all(&self, duration: Option<Duration>) -> Result<(), ServerFnError> {
+        match duration {
+            Some(duration) => {
+                let minutes = duration.as_secs().div_ceil(60).to_string();
+                self.call(&[
+                    ("mode", "config"),
+                    ("name", "set_pause"),
+                    ("value", &minutes),
+                ])
+                .await
+            }
+            None => self.call(&[("mode", "pause")]).await,
+        }
+    }
+
+    async fn resume_all(&self) -> Result<(), ServerFnError> {
+        self.call(&[("mode", "resume")]).await
+    }
+
+    async fn categories(&self) -> Result<Vec<String>, ServerFnError> {
+        let call = self
+            .authenticated_url()?
+            .query_pairs_mut()
+            .append_pair("mode", "get_cats")
+            .finish()
+            .to_string();
+        let categories = self
+            .client
+            .get(call)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Categories>()
+            .await?
+            .categories;
+
+        Ok(categories
+            .into_iter()
+            .filter(|category| category != "*")
+            .collect())
+    }
+
+    async fn set_category(
+        &self,
+        download_item_id: &str,
+        category: Option<String>,
+    ) -> Result<(), ServerFnError> {
+        self.call(&[
+            ("mode", "change_cat"),
+            ("value", download_item_id),
+            // The default category is how SABnzbd has no category
+            ("value2", category.as_deref().unwrap_or("*")),
+        ])
+        .await
+    }
+}
+
+const fn priority_value(priority: NzbPriority) -> &'static str {
+    match priority {
+        NzbPriority::Paused => "-2",
+        NzbPriority::Low => "-1",
+        NzbPriority::Normal => "0",
+        NzbPriority::High => "1",
+        NzbPriority::Force => "2",
     }
 }
 
@@ -79,13 +438,44 @@ impl TryInto<DownloadItem> for Item {
     type Error = ParseFloatError;
 
     fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        let total = megabytes_to_bytes(f64::from_str(&self.mb)?);
+        let left = megabytes_to_bytes(f64::from_str(&self.mbleft)?);
+
         Ok(DownloadItem {
             download_item_id: self.nzo_id,
             download_item_name: self.name,
-            download_mb_left: f64::from_str(&self.mbleft)?,
-            download_mb_total: f64::from_str(&self.mb)?,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: total,
+            download_bytes_done: total.saturating_sub(left),
+            download_bytes_left: left,
+            download_bytes_per_second: Some(self.bytes_per_second),
+            upload_bytes_per_second: None,
+            download_eta: Eta::from_timeleft(&self.timeleft),
+            download_item_category: Some(self.cat).filter(|cat| cat != "*" && !cat.is_empty()),
+            download_item_tags: vec![],
+            download_item_details: DownloadItemDetails::Usenet(UsenetDetails {
+                age: None,
+                average_age: Some(self.avg_age).filter(|age| !age.is_empty()),
+                script: Some(self.script).filter(|script| script != "None" && !script.is_empty()),
+                priority: Some(self.priority),
+                password_protected: self.password.map(|password| !password.is_empty()),
+            }),
+            download_item_added_at: self.time_added.and_then(timestamp),
+            download_item_completed_at: None,
+            download_queue_position: u64::try_from(self.index).ok(),
             download_percentage_complete: f64::from_str(&self.percentage)?,
-            download_item_status: self.status
+            download_item_status: match self.status.as_str() {
+                "Downloading" | "Fetching" | "Grabbing" => DownloadStatus::Downloading,
+                "Queued" | "Propagating" => DownloadStatus::Queued,
+                "Paused" => DownloadStatus::Paused,
+                "Checking" | "QuickCheck" | "Verifying" | "Repairing" => DownloadStatus::Checking,
+                "Extracting" | "Moving" | "Running" => DownloadStatus::Moving,
+                "Completed" => DownloadStatus::Completed,
+                "Failed" => DownloadStatus::Failed,
+                _ => DownloadStatus::Unknown(self.status.clone()),
+            },
+            download_item_status_message: None,
         })
     }
 }
@@ -0,0 +1,30 @@
+use bytesize::ByteSize;
+use serde::{Deserialize, Serialize};
+
+/// A backend's global speed limits
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpeedLimits {
+    /// Bytes per second, `None` when unlimited
+    pub download_limit: Option<u64>,
+    /// Bytes per second, `None` when unlimited or the backend doesn't limit uploads
+    pub upload_limit: Option<u64>,
+    /// Whether the alternative limits are in use, `None` for backends that don't have them
+    pub alternative_speeds: Option<bool>,
+}
+
+impl SpeedLimits {
+    pub fn human_download_limit(&self) -> String {
+        human_limit(self.download_limit)
+    }
+
+    pub fn human_upload_limit(&self) -> String {
+        human_limit(self.upload_limit)
+    }
+}
+
+fn human_limit(limit: Option<u64>) -> String {
+    limit.map_or_else(
+        || "unlimited".to_string(),
+        |limit| format!("{}/s", ByteSize::b(limit)),
+    )
+}
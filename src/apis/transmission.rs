@@ -0,0 +1,277 @@
+use std::num::ParseFloatError;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use dioxus_fullstack::prelude::*;
+use num_traits::cast::ToPrimitive;
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::download_client::{DownloadClient, DownloadClientKind};
+use crate::apis::download_item::{timestamp, DownloadItem};
+use crate::apis::download_item_details::{tracker_host, DownloadItemDetails, TorrentDetails};
+use crate::apis::download_status::DownloadStatus;
+use crate::apis::eta::Eta;
+use crate::apis::reqwest::default_reqwest_client;
+
+const SESSION_ID_HEADER: &str = "X-Transmission-Session-Id";
+/// [`Item::error`] for a problem with the torrent's own data rather than its trackers
+const LOCAL_ERROR: i64 = 3;
+const TORRENT_FIELDS: &[&str] = &[
+    "name",
+    "hashString",
+    "totalSize",
+    "leftUntilDone",
+    "percentDone",
+    "status",
+    "error",
+    "errorString",
+    "rateDownload",
+    "rateUpload",
+    "eta",
+    "labels",
+    "uploadRatio",
+    "peersSendingToUs",
+    "peersGettingFromUs",
+    "downloadDir",
+    "trackers",
+    "addedDate",
+    "doneDate",
+    "queuePosition",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub name: String,
+    pub hash_string: String,
+    pub total_size: i64,
+    pub left_until_done: i64,
+    pub percent_done: f64,
+    pub status: i64,
+    /// 0 when fine, 1 for a tracker warning, 2 for a tracker error and 3 for a local error such as
+    /// a full disk
+    pub error: i64,
+    pub error_string: String,
+    pub rate_download: i64,
+    pub rate_upload: i64,
+    /// Seconds left, or negative when Transmission doesn't know
+    pub eta: i64,
+    /// Only sent by Transmission 3 and later
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Negative when there's no ratio yet, or it's infinite
+    pub upload_ratio: f64,
+    pub peers_sending_to_us: i64,
+    pub peers_getting_from_us: i64,
+    pub download_dir: String,
+    pub trackers: Vec<Tracker>,
+    pub added_date: i64,
+    pub done_date: i64,
+    pub queue_position: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tracker {
+    pub announce: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcRequest<'a, T> {
+    method: &'a str,
+    arguments: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: String,
+    arguments: Option<T>,
+}
+
+#[derive(Debug, Serialize)]
+struct TorrentGetArguments<'a> {
+    fields: &'a [&'a str],
+}
+
+#[derive(Debug, Deserialize)]
+struct TorrentGet {
+    torrents: Vec<Item>,
+}
+
+#[derive(Debug)]
+pub struct Transmission {
+    name: String,
+    endpoint: Url,
+    client: Client,
+    session_id: RwLock<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Transmission {
+    pub fn new(
+        name: String,
+        endpoint: Url,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Self {
+        Self {
+            name,
+            endpoint,
+            client: default_reqwest_client(),
+            session_id: RwLock::new(String::new()),
+            username,
+            password,
+        }
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<Item>, ServerFnError> {
+        let request = RpcRequest {
+            method: "torrent-get",
+            arguments: TorrentGetArguments {
+                fields: TORRENT_FIELDS,
+            },
+        };
+        let initial_response = self.rpc_with_current_session(&request).await?;
+
+        // Transmission answers 409 with a fresh session id whenever ours is missing or stale,
+        // it expects us to remember it and try again.
+        let response = if is_session_conflict(&initial_response) {
+            self.update_session_id(&initial_response)?;
+            self.rpc_with_current_session(&request).await?
+        } else {
+            initial_response
+        };
+
+        let response = response
+            .error_for_status()?
+            .json::<RpcResponse<TorrentGet>>()
+            .await?;
+
+        match response.arguments {
+            Some(arguments) if response.result == "success" => Ok(arguments.torrents),
+            _ => Err(ServerFnError::ServerError(response.result)),
+        }
+    }
+
+    async fn rpc_with_current_session<T: Serialize + Sync>(
+        &self,
+        body: &RpcRequest<'_, T>,
+    ) -> Result<Response, ServerFnError> {
+        let session_id = self.session_id.read().map_or_else(
+            |_| String::new(),
+            |session_id| String::from(session_id.as_str()),
+        );
+        let mut request = self
+            .client
+            .post(self.rpc_url()?)
+            .header(SESSION_ID_HEADER, session_id)
+            .json(body);
+
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+
+        Ok(self.client.execute(request.build()?).await?)
+    }
+
+    fn update_session_id(&self, response: &Response) -> Result<(), ServerFnError> {
+        let session_id = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        self.session_id
+            .write()
+            .as_deref_mut()
+            .map(|x| *x = session_id)
+            .map_err(|err| ServerFnError::ServerError(err.to_string()))?;
+        Ok(())
+    }
+
+    fn rpc_url(&self) -> Result<Url, ServerFnError> {
+        let mut url = self.endpoint.clone();
+        url.path_segments_mut()
+            .map_err(|()| ServerFnError::ServerError("Invalid base url".to_string()))?
+            .push("transmission")
+            .push("rpc");
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl DownloadClient for Transmission {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> DownloadClientKind {
+        DownloadClientKind::Transmission
+    }
+
+    async fn list_items(&self) -> Result<Vec<DownloadItem>, ServerFnError> {
+        self.get_items()
+            .await?
+            .into_iter()
+            .map(TryInto::<DownloadItem>::try_into)
+            .collect::<Result<Vec<DownloadItem>, _>>()
+            .map_err(ServerFnError::from)
+    }
+}
+
+impl TryInto<DownloadItem> for Item {
+    type Error = ParseFloatError;
+
+    fn try_into(self) -> Result<DownloadItem, Self::Error> {
+        Ok(DownloadItem {
+            download_item_id: format!("transmission-{}", self.hash_string),
+            download_item_name: self.name,
+            download_item_backend: String::new(),
+            download_item_capabilities: vec![],
+            download_bytes_total: self.total_size.to_u64().unwrap_or_default(),
+            download_bytes_done: (self.total_size - self.left_until_done)
+                .to_u64()
+                .unwrap_or_default(),
+            download_bytes_left: self.left_until_done.to_u64().unwrap_or_default(),
+            download_bytes_per_second: self.rate_download.to_u64(),
+            upload_bytes_per_second: self.rate_upload.to_u64(),
+            download_eta: self.eta.to_u64().map_or(Eta::Unknown, Eta::from_seconds),
+            download_item_category: None,
+            download_item_tags: self.labels,
+            download_item_details: DownloadItemDetails::Torrent(TorrentDetails {
+                ratio: Some(self.upload_ratio).filter(|ratio| *ratio >= 0.0),
+                seeds: self.peers_sending_to_us.to_u64(),
+                peers: self.peers_getting_from_us.to_u64(),
+                tracker: self
+                    .trackers
+                    .first()
+                    .and_then(|tracker| tracker_host(&tracker.announce)),
+                save_path: Some(self.download_dir),
+            }),
+            download_item_added_at: timestamp(self.added_date),
+            download_item_completed_at: timestamp(self.done_date),
+            download_queue_position: self.queue_position.to_u64(),
+            download_percentage_complete: self.percent_done * 100.0,
+            download_item_status: match (self.error, self.status) {
+                // Tracker problems leave the torrent running, so those only go in the message
+                (LOCAL_ERROR, _) => DownloadStatus::Failed,
+                (_, 0) if self.percent_done >= 1.0 => DownloadStatus::Completed,
+                (_, 0) => DownloadStatus::Paused,
+                (_, 1 | 2) => DownloadStatus::Checking,
+                (_, 3 | 5) => DownloadStatus::Queued,
+                (_, 4) => DownloadStatus::Downloading,
+                (_, 6) => DownloadStatus::Seeding,
+                (_, status) => DownloadStatus::Unknown(status.to_string()),
+            },
+            download_item_status_message: Some(self.error_string)
+                .filter(|message| !message.is_empty()),
+        })
+    }
+}
+
+fn is_session_conflict(response: &Response) -> bool {
+    response.status() == reqwest::StatusCode::CONFLICT
+}
@@ -0,0 +1,301 @@
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// The subset of XML-RPC values we need to read rTorrent's responses
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    String(String),
+    Array(Vec<Self>),
+    Struct(Vec<(String, Self)>),
+}
+
+impl Value {
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            Self::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn into_array(self) -> Option<Vec<Self>> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    fn member(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Struct(members) => members
+                .iter()
+                .find(|(member, _)| member == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+pub fn method_call(method: &str, params: &[&str]) -> String {
+    let params = params.iter().fold(String::new(), |mut params, param| {
+        params.push_str("<param><value><string>");
+        params.push_str(&escape(param));
+        params.push_str("</string></value></param>");
+        params
+    });
+
+    format!(
+        "<?xml version=\"1.0\"?><methodCall><methodName>{}</methodName><params>{params}</params></methodCall>",
+        escape(method)
+    )
+}
+
+/// Read the single value out of a `methodResponse`, turning a fault into an error
+pub fn parse_response(body: &str) -> Result<Value, String> {
+    let mut parser = Parser::new(body);
+    let mut fault = false;
+
+    loop {
+        match parser.next()? {
+            Event::Start(tag) if tag.name().as_ref() == b"fault" => fault = true,
+            Event::Start(tag) if tag.name().as_ref() == b"value" => break,
+            Event::Eof => return Err("No value in XML-RPC response".to_string()),
+            _ => {}
+        }
+    }
+
+    let value = parser.value()?;
+    if fault {
+        return Err(value
+            .member("faultString")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown XML-RPC fault")
+            .to_string());
+    }
+
+    Ok(value)
+}
+
+struct Parser<'a> {
+    reader: Reader<&'a [u8]>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(body: &'a str) -> Self {
+        let mut reader = Reader::from_str(body);
+        reader.trim_text(true);
+        Self { reader }
+    }
+
+    fn next(&mut self) -> Result<Event<'a>, String> {
+        loop {
+            match self.reader.read_event().map_err(|err| err.to_string())? {
+                Event::Decl(_) | Event::Comment(_) | Event::PI(_) | Event::DocType(_) => {}
+                event => return Ok(event),
+            }
+        }
+    }
+
+    fn expect_start(&mut self, name: &str) -> Result<(), String> {
+        match self.next()? {
+            Event::Start(tag) if tag.name().as_ref() == name.as_bytes() => Ok(()),
+            event => Err(format!("Expected <{name}> but found {event:?}")),
+        }
+    }
+
+    fn expect_end(&mut self, name: &str) -> Result<(), String> {
+        match self.next()? {
+            Event::End(tag) if tag.name().as_ref() == name.as_bytes() => Ok(()),
+            event => Err(format!("Expected </{name}> but found {event:?}")),
+        }
+    }
+
+    fn text(&mut self) -> Result<String, String> {
+        let mut text = String::new();
+        loop {
+            match self.next()? {
+                Event::Text(value) => {
+                    text.push_str(&value.unescape().map_err(|err| err.to_string())?);
+                }
+                Event::CData(value) => text.push_str(&String::from_utf8_lossy(&value)),
+                Event::End(_) => return Ok(text),
+                event => return Err(format!("Expected text but found {event:?}")),
+            }
+        }
+    }
+
+    /// Parse a value, having already consumed its opening `<value>`
+    fn value(&mut self) -> Result<Value, String> {
+        let value = match self.next()? {
+            Event::Text(value) => {
+                let value = Value::String(
+                    value
+                        .unescape()
+                        .map_err(|err| err.to_string())?
+                        .into_owned(),
+                );
+                self.expect_end("value")?;
+                return Ok(value);
+            }
+            Event::End(_) => return Ok(Value::String(String::new())),
+            Event::Empty(tag) => match tag.name().as_ref() {
+                b"array" => Value::Array(vec![]),
+                b"struct" => Value::Struct(vec![]),
+                _ => Value::String(String::new()),
+            },
+            Event::Start(tag) => match tag.name().as_ref() {
+                b"i4" | b"i8" | b"int" | b"boolean" => {
+                    let text = self.text()?;
+                    Value::Int(
+                        text.parse()
+                            .map_err(|_| format!("Invalid integer {text}"))?,
+                    )
+                }
+                b"array" => self.array()?,
+                b"struct" => self.structure()?,
+                _ => Value::String(self.text()?),
+            },
+            event => return Err(format!("Expected a value but found {event:?}")),
+        };
+
+        self.expect_end("value")?;
+        Ok(value)
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        let mut values = vec![];
+
+        match self.next()? {
+            Event::Empty(tag) if tag.name().as_ref() == b"data" => {}
+            Event::Start(tag) if tag.name().as_ref() == b"data" => loop {
+                match self.next()? {
+                    Event::Start(tag) if tag.name().as_ref() == b"value" => {
+                        values.push(self.value()?);
+                    }
+                    Event::Empty(tag) if tag.name().as_ref() == b"value" => {
+                        values.push(Value::String(String::new()));
+                    }
+                    Event::End(tag) if tag.name().as_ref() == b"data" => break,
+                    event => return Err(format!("Expected <value> but found {event:?}")),
+                }
+            },
+            event => return Err(format!("Expected <data> but found {event:?}")),
+        }
+
+        self.expect_end("array")?;
+        Ok(Value::Array(values))
+    }
+
+    fn structure(&mut self) -> Result<Value, String> {
+        let mut members = vec![];
+
+        loop {
+            match self.next()? {
+                Event::Start(tag) if tag.name().as_ref() == b"member" => {
+                    self.expect_start("name")?;
+                    let name = self.text()?;
+                    self.expect_start("value")?;
+                    members.push((name, self.value()?));
+                    self.expect_end("member")?;
+                }
+                Event::End(tag) if tag.name().as_ref() == b"struct" => break,
+                event => return Err(format!("Expected <member> but found {event:?}")),
+            }
+        }
+
+        Ok(Value::Struct(members))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_arrays() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<methodResponse>
+<params>
+<param><value><array><data>
+<value><array><data>
+<value><string>ubuntu-22.04.3-desktop-amd64.iso</string></value>
+<value><i8>5037662208</i8></value>
+<value><string>a &amp; b</string></value>
+</data></array></value>
+<value><array><data/></array></value>
+</data></array></value></param>
+</params>
+</methodResponse>"#;
+
+        let rows = parse_response(body)
+            .expect("Response should parse")
+            .into_array()
+            .expect("Response should be an array");
+
+        assert_eq!(
+            rows,
+            vec![
+                Value::Array(vec![
+                    Value::String("ubuntu-22.04.3-desktop-amd64.iso".to_string()),
+                    Value::Int(5_037_662_208),
+                    Value::String("a & b".to_string()),
+                ]),
+                Value::Array(vec![]),
+            ],
+            "Rows should keep their columns in order"
+        );
+    }
+
+    #[test]
+    fn parses_fault() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<methodResponse>
+<fault>
+<value><struct>
+<member><name>faultCode</name><value><i4>-501</i4></value></member>
+<member><name>faultString</name><value><string>Could not find info-hash.</string></value></member>
+</struct></value>
+</fault>
+</methodResponse>"#;
+
+        assert_eq!(
+            parse_response(body),
+            Err("Could not find info-hash.".to_string()),
+            "A fault should become its faultString"
+        );
+    }
+
+    #[test]
+    fn parses_empty_string() {
+        let body = "<methodResponse><params><param><value><string/></value></param></params></methodResponse>";
+
+        assert_eq!(
+            parse_response(body),
+            Ok(Value::String(String::new())),
+            "An empty <string/> should be an empty string"
+        );
+    }
+
+    #[test]
+    fn parses_i8() {
+        let body = "<methodResponse><params><param><value><array><data><value><i8>9007199254740993</i8></value><value><i8>-1</i8></value></data></array></value></param></params></methodResponse>";
+
+        assert_eq!(
+            parse_response(body),
+            Ok(Value::Array(vec![
+                Value::Int(9_007_199_254_740_993),
+                Value::Int(-1)
+            ])),
+            "<i8> values should keep all 64 bits"
+        );
+    }
+}
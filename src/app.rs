@@ -1,18 +1,24 @@
-use std::future::join;
 use std::time::Duration;
 
 use dioxus::prelude::*;
 use dioxus_fullstack::prelude::ServerFnError;
 
-use crate::apis::get_qbt_items;
-use crate::apis::get_sab_items;
-use crate::apis::DownloadItem;
-use crate::components::download_item::DownloadItem;
+use crate::apis::get_disabled_backends;
+use crate::apis::get_download_items;
+use crate::apis::BackendItems;
+use crate::components::add_dialog::AddDialog;
+use crate::components::backend_error::BackendError;
+use crate::components::download_item::{DownloadItem, DraggedItem};
 use crate::components::header::Header;
 use crate::components::footer::Footer;
+use crate::components::sort_select::{SortOrder, SortSelect};
+use crate::components::speed_limits_dialog::SpeedLimitsDialog;
 
 pub fn app(cx: Scope<'_>) -> Element<'_> {
-    let download_items = use_state::<Option<Result<Vec<DownloadItem>, ServerFnError>>>(cx, || None);
+    let download_items = use_state::<Option<Result<Vec<BackendItems>, ServerFnError>>>(cx, || None);
+    let disabled_backends = use_future(cx, (), |()| get_disabled_backends());
+    let sort_order = use_state(cx, SortOrder::default);
+    use_shared_state_provider(cx, || None::<DraggedItem>);
 
     let ws = use_coroutine(cx, |_: UnboundedReceiver<()>| {
         let download_items = download_items.clone();
@@ -27,13 +33,46 @@ pub fn app(cx: Scope<'_>) -> Element<'_> {
     });
     cx.render(rsx! {
         Header {}
+        div {
+            class: "mx-auto max-w-screen-xl mb-4 flex justify-end gap-2",
+            SpeedLimitsDialog {}
+            AddDialog {}
+        }
+        match disabled_backends.value() {
+            Some(Ok(list)) if !list.is_empty() => {
+                render! {
+                    div {
+                        class: "mx-auto max-w-screen-xl grid grid-cols-1 gap-4 mb-4",
+                        for backend in list {
+                            BackendError {
+                                name: &backend.name,
+                                problems: &backend.problems
+                            }
+                        }
+                    }
+                }
+            }
+            _ => None
+        },
         match download_items.get() {
-            Some(Ok(list)) => {
+            Some(Ok(backends)) => {
+                let mut items = backends.iter().filter_map(|backend| backend.items.as_ref().ok()).flatten().collect::<Vec<_>>();
+                sort_order.get().sort(&mut items);
                 render! {
                     div {
                         class: "mx-auto max-w-screen-xl grid grid-cols-1 gap-4",
-                        for item in list {
+                        for (name, err) in backends.iter().filter_map(|backend| Some((&backend.name, backend.items.as_ref().err()?))) {
+                            BackendError {
+                                name: name,
+                                problems: std::slice::from_ref(err)
+                            }
+                        }
+                        SortSelect {
+                            sort_order: sort_order
+                        }
+                        for item in items {
                             DownloadItem {
+                                key: "{item.download_item_backend}/{item.download_item_id}",
                                 download_item: item
                             }
                         }
@@ -50,11 +89,3 @@ pub fn app(cx: Scope<'_>) -> Element<'_> {
         Footer {}
     })
 }
-
-async fn get_download_items() -> Result<Vec<DownloadItem>, ServerFnError> {
-    let items = join!( get_qbt_items(), get_sab_items() ).await;
-    let qbt_items = items.0?;
-    let sab_items = items.1?;
-
-    Ok([qbt_items, sab_items].concat())
-}
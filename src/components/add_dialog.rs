@@ -0,0 +1,310 @@
+use dioxus::prelude::*;
+
+use crate::apis::{
+    add_nzb, add_torrent, get_backends, Capability, DownloadSource, NewNzb, NewTorrent,
+    NzbPriority, PostProcessing,
+};
+
+const INPUT_CLASS: &str = "mt-1 w-full rounded border-gray-200 text-sm dark:border-gray-700 dark:bg-gray-800 dark:text-white";
+const LABEL_CLASS: &str = "block text-sm font-medium text-gray-700 dark:text-gray-200";
+
+
+#[allow(
+    clippy::too_many_lines,
+    reason = "The form's state is shared between the inputs and submitting"
+)]
+pub fn AddDialog(cx: Scope<'_>) -> Element<'_> {
+    let backends = use_future(cx, (), |()| get_backends());
+    let open = use_state(cx, || false);
+    let backend = use_state(cx, String::new);
+    let urls = use_state(cx, String::new);
+    let file = use_state::<Option<(String, Vec<u8>)>>(cx, || None);
+    let category = use_state(cx, String::new);
+    let save_path = use_state(cx, String::new);
+    let paused = use_state(cx, || false);
+    let priority = use_state::<Option<NzbPriority>>(cx, || None);
+    let post_processing = use_state::<Option<PostProcessing>>(cx, || None);
+    let script = use_state(cx, String::new);
+    let error = use_state::<Option<String>>(cx, || None);
+
+    let add_backends = match backends.value() {
+        Some(Ok(list)) => list
+            .iter()
+            .filter(|summary| {
+                summary.supports(Capability::AddTorrent) || summary.supports(Capability::AddNzb)
+            })
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+    let selected = add_backends
+        .iter()
+        .find(|summary| summary.name == *backend.get())
+        .or_else(|| add_backends.first())
+        .copied()?;
+    let selected_backend = &selected.name;
+    let adds_nzb = selected.supports(Capability::AddNzb);
+    let kind_of_file = if adds_nzb { "an .nzb" } else { "a .torrent" };
+
+    let select_backend = move |event: FormEvent| {
+        let picked_nzb = matches!(
+            backends.value(),
+            Some(Ok(list)) if list
+                .iter()
+                .any(|summary| summary.name == event.value && summary.supports(Capability::AddNzb))
+        );
+        // A file picked for one kind of backend can't be sent to the other
+        if picked_nzb != adds_nzb {
+            file.set(None);
+        }
+        backend.set(event.value.clone());
+    };
+
+    let submit = move |_| {
+        let source = match file.get() {
+            Some((file_name, contents)) => DownloadSource::File {
+                file_name: file_name.clone(),
+                contents: contents.clone(),
+            },
+            None if urls.get().trim().is_empty() => {
+                error.set(Some(format!("Add a link or pick {kind_of_file} file")));
+                return;
+            }
+            None => DownloadSource::Urls(urls.get().trim().to_string()),
+        };
+        let backend = selected_backend.clone();
+        let download = if adds_nzb {
+            NewDownload::Nzb(NewNzb {
+                source,
+                category: optional(category.get()),
+                priority: *priority.get(),
+                post_processing: *post_processing.get(),
+                script: optional(script.get()),
+            })
+        } else {
+            NewDownload::Torrent(NewTorrent {
+                source,
+                category: optional(category.get()),
+                save_path: optional(save_path.get()),
+                paused: *paused.get(),
+            })
+        };
+        let (open, urls, file, error) = (open.clone(), urls.clone(), file.clone(), error.clone());
+        cx.spawn(async move {
+            let added = match download {
+                NewDownload::Nzb(nzb) => add_nzb(backend, nzb).await,
+                NewDownload::Torrent(torrent) => add_torrent(backend, torrent).await,
+            };
+            match added {
+                Ok(()) => {
+                    urls.set(String::new());
+                    file.set(None);
+                    error.set(None);
+                    open.set(false);
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    return cx.render(rsx! {
+        button {
+            class: "rounded bg-indigo-600 px-4 py-2 text-sm font-medium text-white hover:bg-indigo-700",
+            onclick: move |_| open.set(true),
+            "Add"
+        }
+        if *open.get() {
+            render! {
+                div { class: "fixed inset-0 z-10 flex items-center justify-center bg-gray-900/50 p-4",
+                    div { role: "dialog", aria_modal: "true", class: "flex w-full max-w-lg flex-col gap-4 rounded-lg bg-white p-6 shadow-lg dark:bg-gray-900",
+                        h2 { class: "text-lg font-medium text-gray-900 dark:text-white", "Add a download" }
+                        label { class: LABEL_CLASS,
+                            "Backend"
+                            select {
+                                class: INPUT_CLASS,
+                                onchange: select_backend,
+                                for summary in add_backends.iter() {
+                                    option {
+                                        value: "{summary.name}",
+                                        selected: summary.name == *selected_backend,
+                                        "{summary.name} ({summary.kind})"
+                                    }
+                                }
+                            }
+                        }
+                        label { class: LABEL_CLASS,
+                            "Links, one per line"
+                            textarea {
+                                class: INPUT_CLASS,
+                                rows: "3",
+                                disabled: file.get().is_some(),
+                                value: "{urls}",
+                                oninput: move |event| urls.set(event.value.clone()),
+                            }
+                        }
+                        label { class: LABEL_CLASS,
+                            "Or {kind_of_file} file"
+                            // A picker each, so switching kinds also empties it
+                            if adds_nzb {
+                                render! { FilePicker { file: file, accept: ".nzb,application/x-nzb" } }
+                            } else {
+                                render! { FilePicker { file: file, accept: ".torrent,application/x-bittorrent" } }
+                            }
+                        }
+                        label { class: LABEL_CLASS,
+                            "Category"
+                            input {
+                                class: INPUT_CLASS,
+                                value: "{category}",
+                                oninput: move |event| category.set(event.value.clone()),
+                            }
+                        }
+                        if adds_nzb {
+                            render! { NzbOptions { priority: priority, post_processing: post_processing, script: script } }
+                        } else {
+                            render! { TorrentOptions { save_path: save_path, paused: paused } }
+                        }
+                        error.get().as_ref().map(|error| rsx! {
+                            p { class: "text-sm text-red-600 dark:text-red-400", "{error}" }
+                        })
+                        div { class: "flex justify-end gap-2",
+                            button {
+                                class: "rounded border border-gray-200 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800",
+                                onclick: move |_| open.set(false),
+                                "Cancel"
+                            }
+                            button {
+                                class: "rounded bg-indigo-600 px-4 py-2 text-sm font-medium text-white hover:bg-indigo-700",
+                                onclick: submit,
+                                "Add"
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            None
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct FilePickerProps<'a> {
+    file: &'a UseState<Option<(String, Vec<u8>)>>,
+    accept: &'static str,
+}
+
+
+pub fn FilePicker<'a>(cx: Scope<'a, FilePickerProps<'a>>) -> Element<'a> {
+    let read_file = move |event: FormEvent| {
+        let file = cx.props.file.clone();
+        let engine = event.files.clone();
+        cx.spawn(async move {
+            let Some(engine) = engine else {
+                return;
+            };
+            let Some(file_name) = engine.files().into_iter().next() else {
+                file.set(None);
+                return;
+            };
+            let contents = engine.read_file(&file_name).await;
+            file.set(contents.map(|contents| (file_name, contents)));
+        });
+    };
+
+    return cx.render(rsx! {
+        input {
+            class: INPUT_CLASS,
+            r#type: "file",
+            accept: cx.props.accept,
+            onchange: read_file,
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct NzbOptionsProps<'a> {
+    priority: &'a UseState<Option<NzbPriority>>,
+    post_processing: &'a UseState<Option<PostProcessing>>,
+    script: &'a UseState<String>,
+}
+
+
+pub fn NzbOptions<'a>(cx: Scope<'a, NzbOptionsProps<'a>>) -> Element<'a> {
+    let NzbOptionsProps { priority, post_processing, script } = cx.props;
+
+    return cx.render(rsx! {
+        label { class: LABEL_CLASS,
+            "Priority"
+            select {
+                class: INPUT_CLASS,
+                onchange: move |event| priority.set(event.value.parse::<usize>().ok().and_then(|index| NzbPriority::ALL.get(index).copied())),
+                option { value: "", selected: priority.get().is_none(), "The category's default" }
+                for (index, choice) in NzbPriority::ALL.iter().enumerate() {
+                    option { value: "{index}", selected: *priority.get() == Some(*choice), "{choice}" }
+                }
+            }
+        }
+        label { class: LABEL_CLASS,
+            "Post-processing"
+            select {
+                class: INPUT_CLASS,
+                onchange: move |event| post_processing.set(event.value.parse::<usize>().ok().and_then(|index| PostProcessing::ALL.get(index).copied())),
+                option { value: "", selected: post_processing.get().is_none(), "The category's default" }
+                for (index, choice) in PostProcessing::ALL.iter().enumerate() {
+                    option { value: "{index}", selected: *post_processing.get() == Some(*choice), "{choice}" }
+                }
+            }
+        }
+        label { class: LABEL_CLASS,
+            "Script"
+            input {
+                class: INPUT_CLASS,
+                placeholder: "The category's default",
+                value: "{script}",
+                oninput: move |event| script.set(event.value.clone()),
+            }
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct TorrentOptionsProps<'a> {
+    save_path: &'a UseState<String>,
+    paused: &'a UseState<bool>,
+}
+
+
+pub fn TorrentOptions<'a>(cx: Scope<'a, TorrentOptionsProps<'a>>) -> Element<'a> {
+    let TorrentOptionsProps { save_path, paused } = cx.props;
+
+    return cx.render(rsx! {
+        label { class: LABEL_CLASS,
+            "Save path"
+            input {
+                class: INPUT_CLASS,
+                placeholder: "The backend's default",
+                value: "{save_path}",
+                oninput: move |event| save_path.set(event.value.clone()),
+            }
+        }
+        label { class: "flex items-center gap-2 text-sm text-gray-700 dark:text-gray-200",
+            input {
+                r#type: "checkbox",
+                checked: *paused.get(),
+                onchange: move |event| paused.set(event.value == "true"),
+            }
+            "Add paused"
+        }
+    });
+}
+
+/// The dialog adds whichever the chosen backend takes
+enum NewDownload {
+    Torrent(NewTorrent),
+    Nzb(NewNzb),
+}
+
+/// Empty inputs leave the option to the backend
+fn optional(value: &str) -> Option<String> {
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
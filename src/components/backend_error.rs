@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+
+#[derive(PartialEq, Eq, Props)]
+pub struct BackendErrorProps<'a> {
+    name: &'a str,
+    problems: &'a [String],
+}
+
+
+pub fn BackendError<'a>(cx: Scope<'a, BackendErrorProps<'a>>) -> Element<'a> {
+    return cx.render(rsx! {
+        div { role: "alert", class: "rounded border-s-4 border-red-500 bg-red-50 p-4 dark:border-red-600 dark:bg-red-900",
+            strong { class: "block font-medium text-red-800 dark:text-red-100",
+                "{cx.props.name} is unavailable"
+            }
+            ul { class: "mt-2 text-sm text-red-700 dark:text-red-200",
+                for problem in cx.props.problems.iter() {
+                    li { "{problem}" }
+                }
+            }
+        }
+    });
+}
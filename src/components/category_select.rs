@@ -0,0 +1,77 @@
+use dioxus::prelude::*;
+
+use crate::apis::{get_categories, set_download_item_category, DownloadItem};
+
+#[derive(PartialEq, Props)]
+pub struct CategorySelectProps<'a> {
+    download_item: &'a DownloadItem,
+}
+
+
+pub fn CategorySelect<'a>(cx: Scope<'a, CategorySelectProps<'a>>) -> Element<'a> {
+    let item = cx.props.download_item;
+    let editing = use_state(cx, || false);
+    let error = use_state::<Option<String>>(cx, || None);
+    // Only asked for once the select is opened, rather than by every card on every refresh
+    let categories = use_future(
+        cx,
+        (editing.get(), &item.download_item_backend),
+        |(editing, backend)| async move {
+            if editing {
+                get_categories(backend).await
+            } else {
+                Ok(vec![])
+            }
+        },
+    );
+
+    let set_category = move |event: FormEvent| {
+        let (editing, error) = (editing.clone(), error.clone());
+        let backend = item.download_item_backend.clone();
+        let download_item_id = item.download_item_id.clone();
+        let category = Some(event.value.clone()).filter(|category| !category.is_empty());
+        cx.spawn(async move {
+            let result = set_download_item_category(backend, download_item_id, category).await;
+            error.set(result.err().map(|err| err.to_string()));
+            editing.set(false);
+        });
+    };
+
+    let current = item.download_item_category.as_deref().unwrap_or_default();
+
+    return cx.render(rsx! {
+        match (*editing.get(), categories.value()) {
+            (false, _) => render! {
+                button {
+                    class: "rounded border border-gray-200 px-3 py-1 text-xs font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800",
+                    onclick: move |_| editing.set(true),
+                    "Category"
+                }
+            },
+            (true, Some(Ok(list))) => render! {
+                select {
+                    class: "rounded border-gray-200 py-1 text-xs text-gray-700 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-200",
+                    onchange: set_category,
+                    option { value: "", selected: current.is_empty(), "No category" }
+                    for category in list.iter() {
+                        option { value: "{category}", selected: category == current, "{category}" }
+                    }
+                }
+                button {
+                    class: "text-xs text-gray-500 hover:underline dark:text-gray-400",
+                    onclick: move |_| editing.set(false),
+                    "Cancel"
+                }
+            },
+            (true, Some(Err(err))) => render! {
+                span { class: "text-xs text-red-600 dark:text-red-400", "{err}" }
+            },
+            (true, None) => render! {
+                span { class: "text-xs text-gray-500 dark:text-gray-400", "Loading categories" }
+            },
+        }
+        error.get().as_ref().map(|error| rsx! {
+            span { class: "text-xs text-red-600 dark:text-red-400", "{error}" }
+        })
+    });
+}
@@ -1,9 +1,22 @@
 use dioxus::prelude::*;
 
-use crate::apis::DownloadItem;
+use log::warn;
+
+use crate::apis::{move_download_item, Capability, DownloadItem, DownloadStatus, QueueMove};
+use crate::components::item_actions::ItemActions;
+use crate::components::item_details::ItemDetails;
 use crate::components::progress::Progress;
 use bytesize::ByteSize;
 
+/// The item being dragged to a new place in its queue, shared by every card so the one it's dropped
+/// on knows what to move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DraggedItem {
+    backend: String,
+    download_item_id: String,
+    position: u64,
+}
+
 #[derive(PartialEq, Props)]
 pub struct DownloadItemProps<'a> {
     download_item: &'a DownloadItem,
@@ -11,50 +24,73 @@ pub struct DownloadItemProps<'a> {
 
 
 pub fn DownloadItem<'a>(cx: Scope<'a, DownloadItemProps<'a>>) -> Element<'a> {
+    let dragged = use_shared_state::<Option<DraggedItem>>(cx);
+    let item = cx.props.download_item;
+    let position = item.download_queue_position.filter(|_| item.supports(Capability::Reorder));
+
+    let drag_start = move |_| {
+        if let (Some(dragged), Some(position)) = (dragged, position) {
+            *dragged.write() = Some(DraggedItem {
+                backend: item.download_item_backend.clone(),
+                download_item_id: item.download_item_id.clone(),
+                position,
+            });
+        }
+    };
+
+    let drop_here = move |_| {
+        let dragged = dragged.and_then(|dragged| dragged.write().take());
+        let (Some(dragged), Some(to)) = (dragged, position) else {
+            return;
+        };
+        // Positions in different backends' queues have nothing to do with each other
+        if dragged.backend != item.download_item_backend || dragged.position == to {
+            return;
+        }
+        cx.spawn(async move {
+            let DraggedItem { backend, download_item_id, position: from } = dragged;
+            let queue_move = QueueMove::To { from, to };
+            if let Err(err) = move_download_item(backend, download_item_id.clone(), queue_move).await {
+                warn!("Couldn't move {download_item_id}: {err}");
+            }
+        });
+    };
+
     return cx.render(rsx! {
         article { class: "flex flex-col gap-4 rounded-lg border border-gray-100 bg-white p-6 dark:border-gray-800 dark:bg-gray-900",
-            h2 { class: "flex flex-row justify-end gap-2",
-                div { class: "inline-flex gap-2 self-end rounded bg-green-100 p-1 text-green-600 dark:bg-green-700 dark:text-green-50",
-                    svg {
-                        xmlns: "http://www.w3.org/2000/svg",
-                        fill: "none",
-                        stroke_width: "1.3",
-                        view_box: "0 0 24 24",
-                        class: "h-4 w-4",
-                        color: "currentColor",
-                        path {
-                            stroke: "currentColor",
-                            stroke_width: "1.3",
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            d: "M6 20h12M12 4v12m0 0 3.5-3.5M12 16l-3.5-3.5",
-                        }
-                    }
-                    span { class: "text-xs font-medium", "67.81 MB/s" }
+            draggable: "{position.is_some()}",
+            prevent_default: "ondragover ondrop",
+            ondragstart: drag_start,
+            ondragover: move |_| {},
+            ondrop: drop_here,
+            ondragend: move |_| {
+                if let Some(dragged) = dragged {
+                    *dragged.write() = None;
                 }
-                div { class: "inline-flex gap-2 self-end rounded bg-blue-100 p-1 text-blue-600 dark:bg-blue-700 dark:text-blue-50",
-                    svg {
-                        xmlns: "http://www.w3.org/2000/svg",
-                        class: "h-4 w-4",
-                        fill: "none",
-                        stroke_width: "1.3",
-                        view_box: "0 0 24 24",
-                        path {
-                            stroke: "currentColor",
-                            stroke_width: "1.3",
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            d: "M6 20h12M12 16V4m0 0 3.5 3.5M12 4 8.5 7.5"
-                        }
-                    }
-                    span { class: "text-xs font-medium", "30.6 MB/s" }
+            },
+            h2 { class: "flex flex-row justify-end gap-2",
+                span { class: "mr-auto inline-flex items-center rounded px-2 py-1 text-xs font-medium {status_class(&cx.props.download_item.download_item_status)}",
+                    "{cx.props.download_item.download_item_status}"
                 }
+                ItemRates { download_item: &cx.props.download_item }
             }
             Progress { download_item: &cx.props.download_item }
             div {
                 strong { class: "block text-sm font-medium text-gray-500 dark:text-gray-400",
                     "{cx.props.download_item.download_item_name}"
                 }
+                div { class: "my-1 flex flex-wrap gap-1",
+                    cx.props.download_item.download_item_category.as_ref().map(|category| rsx! {
+                        span { class: "rounded-full bg-purple-100 px-2.5 py-0.5 text-xs text-purple-700 dark:bg-purple-700 dark:text-purple-100",
+                            "{category}"
+                        }
+                    })
+                    for tag in cx.props.download_item.download_item_tags.iter() {
+                        span { class: "rounded-full bg-gray-100 px-2.5 py-0.5 text-xs text-gray-700 dark:bg-gray-700 dark:text-gray-100",
+                            "{tag}"
+                        }
+                    }
+                }
                 p {
                     span { class: "text-2xl font-medium text-gray-900 dark:text-white mr-2",
                         "{cx.props.download_item.human_amount_left()}"
@@ -63,7 +99,82 @@ pub fn DownloadItem<'a>(cx: Scope<'a, DownloadItemProps<'a>>) -> Element<'a> {
                         " of {cx.props.download_item.human_amount_total()}"
                     }
                 }
+                p { class: "mt-1 text-xs text-gray-500 dark:text-gray-400",
+                    "ETA {cx.props.download_item.download_eta.human_remaining()}"
+                    cx.props.download_item.download_eta.human_done_at().map(|done_at| rsx! { ", done at {done_at}" })
+                }
+                p { class: "mt-1 flex flex-wrap gap-x-3 text-xs text-gray-500 dark:text-gray-400",
+                    cx.props.download_item.human_queue_position().map(|position| rsx! { span { "{position} in queue" } })
+                    cx.props.download_item.human_added_at().map(|added_at| rsx! { span { "Added {added_at}" } })
+                    cx.props.download_item.human_completed_at().map(|completed_at| rsx! { span { "Completed {completed_at}" } })
+                }
+                cx.props.download_item.download_item_status_message.as_ref().map(|message| rsx! {
+                    p { class: "mt-1 text-xs text-gray-500 dark:text-gray-400", "{message}" }
+                })
+                ItemDetails { details: &cx.props.download_item.download_item_details }
+                ItemActions { download_item: &cx.props.download_item }
             }
         }
     });
+}
+
+#[derive(PartialEq, Props)]
+pub struct ItemRatesProps<'a> {
+    download_item: &'a DownloadItem,
+}
+
+
+pub fn ItemRates<'a>(cx: Scope<'a, ItemRatesProps<'a>>) -> Element<'a> {
+    return cx.render(rsx! {
+        cx.props.download_item.human_download_rate().map(|rate| rsx! {
+            div { class: "inline-flex gap-2 self-end rounded bg-green-100 p-1 text-green-600 dark:bg-green-700 dark:text-green-50",
+                svg {
+                    xmlns: "http://www.w3.org/2000/svg",
+                    fill: "none",
+                    stroke_width: "1.3",
+                    view_box: "0 0 24 24",
+                    class: "h-4 w-4",
+                    color: "currentColor",
+                    path {
+                        stroke: "currentColor",
+                        stroke_width: "1.3",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        d: "M6 20h12M12 4v12m0 0 3.5-3.5M12 16l-3.5-3.5",
+                    }
+                }
+                span { class: "text-xs font-medium", "{rate}" }
+            }
+        })
+        cx.props.download_item.human_upload_rate().map(|rate| rsx! {
+            div { class: "inline-flex gap-2 self-end rounded bg-blue-100 p-1 text-blue-600 dark:bg-blue-700 dark:text-blue-50",
+                svg {
+                    xmlns: "http://www.w3.org/2000/svg",
+                    class: "h-4 w-4",
+                    fill: "none",
+                    stroke_width: "1.3",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke: "currentColor",
+                        stroke_width: "1.3",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        d: "M6 20h12M12 16V4m0 0 3.5 3.5M12 4 8.5 7.5"
+                    }
+                }
+                span { class: "text-xs font-medium", "{rate}" }
+            }
+        })
+    });
+}
+
+const fn status_class(status: &DownloadStatus) -> &'static str {
+    match status {
+        DownloadStatus::Downloading | DownloadStatus::Moving => "bg-indigo-100 text-indigo-700 dark:bg-indigo-700 dark:text-indigo-50",
+        DownloadStatus::Seeding | DownloadStatus::Completed => "bg-green-100 text-green-700 dark:bg-green-700 dark:text-green-50",
+        DownloadStatus::Queued | DownloadStatus::Checking => "bg-blue-100 text-blue-700 dark:bg-blue-700 dark:text-blue-50",
+        DownloadStatus::Paused | DownloadStatus::Unknown(_) => "bg-gray-100 text-gray-700 dark:bg-gray-700 dark:text-gray-50",
+        DownloadStatus::Stalled => "bg-amber-100 text-amber-700 dark:bg-amber-700 dark:text-amber-50",
+        DownloadStatus::Failed => "bg-red-100 text-red-700 dark:bg-red-700 dark:text-red-50",
+    }
 }
\ No newline at end of file
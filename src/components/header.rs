@@ -1,6 +1,8 @@
 use dioxus::prelude::*;
 use dioxus_fullstack::prelude::*;
 
+use crate::components::pause_controls::PauseControls;
+
 
 pub fn Header(cx: Scope<'_>) -> Element<'_> {
     return cx.render(
@@ -20,6 +22,7 @@ pub fn Header(cx: Scope<'_>) -> Element<'_> {
                             }
                         }
                     }
+                    PauseControls {}
                 }
             }
         }
@@ -0,0 +1,237 @@
+use dioxus::prelude::*;
+
+use crate::apis::{
+    delete_download_item, move_download_item, pause_download_item, resume_download_item,
+    set_download_item_priority, Capability, DownloadItem, DownloadStatus, NzbPriority, QueueMove,
+};
+
+use crate::components::category_select::CategorySelect;
+
+const BUTTON_CLASS: &str = "rounded border border-gray-200 px-3 py-1 text-xs font-medium text-gray-700 hover:bg-gray-50 disabled:opacity-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800";
+
+#[derive(PartialEq, Props)]
+pub struct ItemActionsProps<'a> {
+    download_item: &'a DownloadItem,
+}
+
+
+pub fn ItemActions<'a>(cx: Scope<'a, ItemActionsProps<'a>>) -> Element<'a> {
+    let error = use_state::<Option<String>>(cx, || None);
+    let confirming_delete = use_state(cx, || false);
+    let item = cx.props.download_item;
+    let paused = item.download_item_status == DownloadStatus::Paused;
+    let pause_label = if paused { "Resume" } else { "Pause" };
+
+    let toggle_pause = move |_| {
+        let error = error.clone();
+        let backend = item.download_item_backend.clone();
+        let download_item_id = item.download_item_id.clone();
+        cx.spawn(async move {
+            let result = if paused {
+                resume_download_item(backend, download_item_id).await
+            } else {
+                pause_download_item(backend, download_item_id).await
+            };
+            error.set(result.err().map(|err| err.to_string()));
+        });
+    };
+
+    let set_priority = move |event: FormEvent| {
+        let Some(priority) = event
+            .value
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| NzbPriority::ALL.get(index).copied())
+        else {
+            return;
+        };
+        let error = error.clone();
+        let backend = item.download_item_backend.clone();
+        let download_item_id = item.download_item_id.clone();
+        cx.spawn(async move {
+            let result = set_download_item_priority(backend, download_item_id, priority).await;
+            error.set(result.err().map(|err| err.to_string()));
+        });
+    };
+
+    return cx.render(rsx! {
+        div { class: "mt-3 flex flex-wrap items-center gap-2",
+            if item.supports(Capability::PauseResume) {
+                render! {
+                    button {
+                        class: BUTTON_CLASS,
+                        onclick: toggle_pause,
+                        "{pause_label}"
+                    }
+                }
+            } else {
+                None
+            }
+            match item.download_queue_position {
+                Some(position) if item.supports(Capability::Reorder) => render! {
+                    QueueButtons { download_item: item, position: position, error: error }
+                },
+                _ => None,
+            }
+            if item.supports(Capability::SetPriority) {
+                render! {
+                    select {
+                        class: "rounded border-gray-200 py-1 text-xs text-gray-700 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-200",
+                        onchange: set_priority,
+                        option { value: "", "Set priority" }
+                        for (index, choice) in NzbPriority::ALL.iter().enumerate() {
+                            option { value: "{index}", "{choice}" }
+                        }
+                    }
+                }
+            } else {
+                None
+            }
+            if item.supports(Capability::SetCategory) {
+                render! {
+                    CategorySelect { download_item: item }
+                }
+            } else {
+                None
+            }
+            if item.supports(Capability::Delete) {
+                render! {
+                    button {
+                        class: "rounded border border-red-200 px-3 py-1 text-xs font-medium text-red-700 hover:bg-red-50 dark:border-red-800 dark:text-red-300 dark:hover:bg-red-950",
+                        onclick: move |_| confirming_delete.set(true),
+                        "Delete"
+                    }
+                }
+            } else {
+                None
+            }
+            error.get().as_ref().map(|error| rsx! {
+                p { class: "text-xs text-red-600 dark:text-red-400", "{error}" }
+            })
+            if *confirming_delete.get() {
+                render! {
+                    DeleteDialog { download_item: item, confirming_delete: confirming_delete, error: error }
+                }
+            } else {
+                None
+            }
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct QueueButtonsProps<'a> {
+    download_item: &'a DownloadItem,
+    position: u64,
+    error: &'a UseState<Option<String>>,
+}
+
+
+pub fn QueueButtons<'a>(cx: Scope<'a, QueueButtonsProps<'a>>) -> Element<'a> {
+    let QueueButtonsProps { download_item: item, position, error } = cx.props;
+    let position = *position;
+
+    let move_item = move |queue_move: QueueMove| {
+        let error = (*error).clone();
+        let backend = item.download_item_backend.clone();
+        let download_item_id = item.download_item_id.clone();
+        cx.spawn(async move {
+            let result = move_download_item(backend, download_item_id, queue_move).await;
+            error.set(result.err().map(|err| err.to_string()));
+        });
+    };
+
+    return cx.render(rsx! {
+        div { class: "inline-flex gap-1",
+            button {
+                class: BUTTON_CLASS,
+                title: "Move to the top of the queue",
+                disabled: position == 0,
+                onclick: move |_| move_item(QueueMove::Top),
+                "Top"
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Move up the queue",
+                disabled: position == 0,
+                onclick: move |_| {
+                    if let Some(queue_move) = QueueMove::up(position) {
+                        move_item(queue_move);
+                    }
+                },
+                "Up"
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Move down the queue",
+                onclick: move |_| move_item(QueueMove::down(position)),
+                "Down"
+            }
+            button {
+                class: BUTTON_CLASS,
+                title: "Move to the bottom of the queue",
+                onclick: move |_| move_item(QueueMove::Bottom),
+                "Bottom"
+            }
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct DeleteDialogProps<'a> {
+    download_item: &'a DownloadItem,
+    confirming_delete: &'a UseState<bool>,
+    error: &'a UseState<Option<String>>,
+}
+
+
+pub fn DeleteDialog<'a>(cx: Scope<'a, DeleteDialogProps<'a>>) -> Element<'a> {
+    let DeleteDialogProps { download_item: item, confirming_delete, error } = cx.props;
+    let delete_files = use_state(cx, || false);
+
+    let delete = move |_| {
+        let error = (*error).clone();
+        let confirming_delete = (*confirming_delete).clone();
+        let backend = item.download_item_backend.clone();
+        let download_item_id = item.download_item_id.clone();
+        let delete_files = *delete_files.get();
+        cx.spawn(async move {
+            let result = delete_download_item(backend, download_item_id, delete_files).await;
+            confirming_delete.set(false);
+            error.set(result.err().map(|err| err.to_string()));
+        });
+    };
+
+    return cx.render(rsx! {
+        div { class: "fixed inset-0 z-10 flex items-center justify-center bg-gray-900/50 p-4",
+            div { role: "dialog", aria_modal: "true", class: "w-full max-w-md rounded-lg bg-white p-6 shadow-lg dark:bg-gray-900",
+                h2 { class: "text-lg font-medium text-gray-900 dark:text-white",
+                    "Delete {item.download_item_name}?"
+                }
+                p { class: "mt-2 text-sm text-gray-500 dark:text-gray-400",
+                    "It will be removed from {item.download_item_backend}."
+                }
+                label { class: "mt-4 flex items-center gap-2 text-sm text-gray-700 dark:text-gray-200",
+                    input {
+                        r#type: "checkbox",
+                        checked: *delete_files.get(),
+                        onchange: move |event| delete_files.set(event.value == "true"),
+                    }
+                    "Also delete the downloaded files"
+                }
+                div { class: "mt-6 flex justify-end gap-2",
+                    button {
+                        class: "rounded border border-gray-200 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800",
+                        onclick: move |_| confirming_delete.set(false),
+                        "Cancel"
+                    }
+                    button {
+                        class: "rounded bg-red-600 px-4 py-2 text-sm font-medium text-white hover:bg-red-700",
+                        onclick: delete,
+                        "Delete"
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,28 @@
+use dioxus::prelude::*;
+
+use crate::apis::DownloadItemDetails;
+
+#[derive(PartialEq, Props)]
+pub struct ItemDetailsProps<'a> {
+    details: &'a DownloadItemDetails,
+}
+
+
+pub fn ItemDetails<'a>(cx: Scope<'a, ItemDetailsProps<'a>>) -> Element<'a> {
+    let rows = cx.props.details.human_rows();
+    if rows.is_empty() {
+        return None;
+    }
+
+    return cx.render(rsx! {
+        details { class: "mt-2 text-xs text-gray-500 dark:text-gray-400",
+            summary { class: "cursor-pointer", "Details" }
+            dl { class: "mt-1 grid grid-cols-[max-content_1fr] gap-x-4 gap-y-1",
+                for (label, value) in rows.into_iter() {
+                    dt { class: "font-medium", "{label}" }
+                    dd { class: "truncate", title: "{value}", "{value}" }
+                }
+            }
+        }
+    });
+}
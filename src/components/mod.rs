@@ -1,4 +1,12 @@
-pub(crate) mod header;
-pub(crate) mod footer;
-pub(crate) mod progress;
-pub(crate) mod download_item;
+pub mod header;
+pub mod footer;
+pub mod progress;
+pub mod download_item;
+pub mod backend_error;
+pub mod item_details;
+pub mod sort_select;
+pub mod item_actions;
+pub mod add_dialog;
+pub mod speed_limits_dialog;
+pub mod pause_controls;
+pub mod category_select;
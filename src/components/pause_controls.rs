@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use chrono::Local;
+use dioxus::prelude::*;
+
+use crate::apis::{get_backends, get_pause_state, pause_all, resume_all, Capability, PauseState};
+
+const BUTTON_CLASS: &str = "rounded border border-gray-200 px-3 py-1 text-xs font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800";
+
+/// How long "pause everything" lasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseFor {
+    /// Until someone resumes it
+    Now,
+    HalfAnHour,
+    AnHour,
+    /// Until midnight
+    Tomorrow,
+}
+
+impl PauseFor {
+    const ALL: [Self; 4] = [Self::Now, Self::HalfAnHour, Self::AnHour, Self::Tomorrow];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Now => "Pause all",
+            Self::HalfAnHour => "30 minutes",
+            Self::AnHour => "1 hour",
+            Self::Tomorrow => "Until tomorrow",
+        }
+    }
+
+    fn duration(self) -> Option<Duration> {
+        match self {
+            Self::Now => None,
+            Self::HalfAnHour => Some(Duration::from_mins(30)),
+            Self::AnHour => Some(Duration::from_hours(1)),
+            Self::Tomorrow => {
+                let now = Local::now();
+                let midnight = now
+                    .date_naive()
+                    .succ_opt()?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_local_timezone(Local)
+                    .earliest()?;
+                (midnight - now).to_std().ok()
+            }
+        }
+    }
+}
+
+
+pub fn PauseControls(cx: Scope<'_>) -> Element<'_> {
+    let backends = use_future(cx, (), |()| get_backends());
+    let pause_state = use_state::<Option<PauseState>>(cx, || None);
+    let error = use_state::<Option<String>>(cx, || None);
+
+    // Polled, as the server resumes everything by itself once a timed pause is over
+    use_coroutine(cx, |_: UnboundedReceiver<()>| {
+        let pause_state = pause_state.clone();
+        async move {
+            loop {
+                if let Ok(state) = get_pause_state().await {
+                    pause_state.set(Some(state));
+                }
+
+                gloo_timers::future::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    });
+
+    let can_pause = match backends.value() {
+        Some(Ok(list)) => list.iter().any(|summary| summary.supports(Capability::PauseAll)),
+        _ => false,
+    };
+    let state = (*pause_state.get()).filter(|_| can_pause)?;
+
+    let pause = move |pause_for: PauseFor| {
+        let (pause_state, error) = (pause_state.clone(), error.clone());
+        cx.spawn(async move {
+            let result = pause_all(pause_for.duration()).await;
+            error.set(result.err().map(|err| err.to_string()));
+            pause_state.set(get_pause_state().await.ok());
+        });
+    };
+
+    let resume = move |_| {
+        let (pause_state, error) = (pause_state.clone(), error.clone());
+        cx.spawn(async move {
+            let result = resume_all().await;
+            error.set(result.err().map(|err| err.to_string()));
+            pause_state.set(get_pause_state().await.ok());
+        });
+    };
+
+    return cx.render(rsx! {
+        div { class: "ml-auto flex flex-wrap items-center justify-end gap-2",
+            error.get().as_ref().map(|error| rsx! {
+                span { class: "text-xs text-red-600 dark:text-red-400", "{error}" }
+            })
+            if state.is_paused() {
+                render! {
+                    span { class: "rounded bg-amber-100 px-2 py-1 text-xs font-medium text-amber-700 dark:bg-amber-700 dark:text-amber-50",
+                        "{state.human()}"
+                    }
+                    button { class: BUTTON_CLASS, onclick: resume, "Resume all" }
+                }
+            } else {
+                render! {
+                    for pause_for in PauseFor::ALL {
+                        button {
+                            class: BUTTON_CLASS,
+                            onclick: move |_| pause(pause_for),
+                            "{pause_for.label()}"
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
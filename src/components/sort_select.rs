@@ -0,0 +1,105 @@
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+use crate::apis::{DownloadItem, Eta};
+
+/// How the items of every backend are ordered in the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Grouped by backend, in the order each backend returned them
+    Backend,
+    /// Grouped by backend like [`SortOrder::Backend`], what each will download next at the top,
+    /// and where items can be dragged to reorder them
+    #[default]
+    QueuePosition,
+    /// Whatever will finish first at the top
+    Eta,
+    RecentlyAdded,
+    RecentlyCompleted,
+}
+
+impl SortOrder {
+    pub const ALL: [Self; 5] = [
+        Self::Backend,
+        Self::QueuePosition,
+        Self::Eta,
+        Self::RecentlyAdded,
+        Self::RecentlyCompleted,
+    ];
+
+    /// Items missing whatever is being sorted by go last, the sort is stable so they keep
+    /// the backend order between them
+    pub fn sort(self, items: &mut [&DownloadItem]) {
+        match self {
+            Self::Backend => {}
+            // Positions are only comparable within one backend
+            Self::QueuePosition => {
+                for backend_items in items.chunk_by_mut(|item, next| {
+                    item.download_item_backend == next.download_item_backend
+                }) {
+                    backend_items.sort_by_key(|item| {
+                        let position = item.download_queue_position;
+                        (position.is_none(), position)
+                    });
+                }
+            }
+            Self::Eta => items.sort_by_key(|item| match item.download_eta {
+                Eta::Remaining(remaining) => (false, remaining),
+                Eta::Unknown => (true, Duration::ZERO),
+            }),
+            Self::RecentlyAdded => items.sort_by_key(|item| {
+                let added_at = item.download_item_added_at;
+                (added_at.is_none(), Reverse(added_at))
+            }),
+            Self::RecentlyCompleted => items.sort_by_key(|item| {
+                let completed_at = item.download_item_completed_at;
+                (completed_at.is_none(), Reverse(completed_at))
+            }),
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Backend => "Backend",
+            Self::QueuePosition => "Queue position",
+            Self::Eta => "Finishing first",
+            Self::RecentlyAdded => "Recently added",
+            Self::RecentlyCompleted => "Recently completed",
+        })
+    }
+}
+
+#[derive(PartialEq, Props)]
+pub struct SortSelectProps<'a> {
+    sort_order: &'a UseState<SortOrder>,
+}
+
+
+pub fn SortSelect<'a>(cx: Scope<'a, SortSelectProps<'a>>) -> Element<'a> {
+    return cx.render(rsx! {
+        label { class: "flex items-center justify-end gap-2 text-sm text-gray-500 dark:text-gray-400",
+            "Sort by"
+            select {
+                class: "rounded border-gray-300 bg-white py-1 text-sm text-gray-700 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-200",
+                onchange: move |event| {
+                    let sort_order = event.value.parse::<usize>().ok().and_then(|index| SortOrder::ALL.get(index));
+                    if let Some(sort_order) = sort_order {
+                        cx.props.sort_order.set(*sort_order);
+                    }
+                },
+                for (index, sort_order) in SortOrder::ALL.iter().enumerate() {
+                    option {
+                        value: "{index}",
+                        selected: *cx.props.sort_order.get() == *sort_order,
+                        "{sort_order}"
+                    }
+                }
+            }
+        }
+    });
+}
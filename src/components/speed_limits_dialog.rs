@@ -0,0 +1,212 @@
+use dioxus::prelude::*;
+
+use crate::apis::{
+    get_backends, get_speed_limits, set_alternative_speeds, set_speed_limits, BackendSummary,
+    Capability,
+};
+
+const INPUT_CLASS: &str = "mt-1 w-full rounded border-gray-200 text-sm dark:border-gray-700 dark:bg-gray-800 dark:text-white";
+const LABEL_CLASS: &str = "block text-sm font-medium text-gray-700 dark:text-gray-200";
+
+
+pub fn SpeedLimitsDialog(cx: Scope<'_>) -> Element<'_> {
+    let backends = use_future(cx, (), |()| get_backends());
+    let open = use_state(cx, || false);
+
+    let limited_backends = match backends.value() {
+        Some(Ok(list)) => list
+            .iter()
+            .filter(|summary| summary.supports(Capability::SpeedLimits))
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+    if limited_backends.is_empty() {
+        return None;
+    }
+
+    return cx.render(rsx! {
+        button {
+            class: "rounded border border-gray-200 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800",
+            onclick: move |_| open.set(true),
+            "Speed limits"
+        }
+        if *open.get() {
+            render! {
+                div { class: "fixed inset-0 z-10 flex items-center justify-center bg-gray-900/50 p-4",
+                    div { role: "dialog", aria_modal: "true", class: "flex w-full max-w-lg flex-col gap-6 rounded-lg bg-white p-6 shadow-lg dark:bg-gray-900",
+                        h2 { class: "text-lg font-medium text-gray-900 dark:text-white", "Speed limits" }
+                        for summary in limited_backends {
+                            BackendSpeedLimits { summary: summary }
+                        }
+                        div { class: "flex justify-end",
+                            button {
+                                class: "rounded border border-gray-200 px-4 py-2 text-sm font-medium text-gray-700 hover:bg-gray-50 dark:border-gray-700 dark:text-gray-200 dark:hover:bg-gray-800",
+                                onclick: move |_| open.set(false),
+                                "Close"
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            None
+        }
+    });
+}
+
+#[derive(PartialEq, Eq, Props)]
+pub struct BackendSpeedLimitsProps<'a> {
+    summary: &'a BackendSummary,
+}
+
+
+pub fn BackendSpeedLimits<'a>(cx: Scope<'a, BackendSpeedLimitsProps<'a>>) -> Element<'a> {
+    let summary = cx.props.summary;
+    let name = summary.name.clone();
+    // Bumped after every change, to read the limits back
+    let refreshes = use_state(cx, || 0_u32);
+    let download_limit = use_state(cx, String::new);
+    let upload_limit = use_state(cx, String::new);
+    // The inputs start out as the current limits, so applying one leaves the other as it was
+    let limits = use_future(cx, (refreshes.get(),), move |_| {
+        let (download_limit, upload_limit) = (download_limit.clone(), upload_limit.clone());
+        async move {
+            let limits = get_speed_limits(name).await;
+            if let Ok(current) = &limits {
+                download_limit.set(limit_to_kibibytes(current.download_limit));
+                upload_limit.set(limit_to_kibibytes(current.upload_limit));
+            }
+            limits
+        }
+    });
+    let error = use_state::<Option<String>>(cx, || None);
+
+    let apply = move |_| {
+        let (refreshes, error) = (refreshes.clone(), error.clone());
+        let backend = summary.name.clone();
+        let download_limit = kibibytes_to_limit(download_limit.get());
+        let upload_limit = kibibytes_to_limit(upload_limit.get());
+        cx.spawn(async move {
+            let result = set_speed_limits(backend, download_limit, upload_limit).await;
+            error.set(result.err().map(|err| err.to_string()));
+            refreshes.modify(|refreshes| refreshes.wrapping_add(1));
+        });
+    };
+
+    return cx.render(rsx! {
+        section { class: "flex flex-col gap-3",
+            h3 { class: "font-medium text-gray-900 dark:text-white", "{summary.name} ({summary.kind})" }
+            match limits.value() {
+                Some(Ok(current)) => render! {
+                    p { class: "text-xs text-gray-500 dark:text-gray-400",
+                        "Now {current.human_download_limit()} down"
+                        if summary.supports(Capability::UploadLimit) {
+                            render! { ", {current.human_upload_limit()} up" }
+                        } else {
+                            None
+                        }
+                    }
+                    match (summary.supports(Capability::AlternativeSpeeds), current.alternative_speeds) {
+                        (true, Some(enabled)) => render! {
+                            AlternativeSpeeds { backend: &summary.name, enabled: enabled, refreshes: refreshes, error: error }
+                        },
+                        _ => None,
+                    }
+                },
+                Some(Err(err)) => render! {
+                    p { class: "text-xs text-red-600 dark:text-red-400", "{err}" }
+                },
+                None => render! {
+                    p { class: "text-xs text-gray-500 dark:text-gray-400", "Loading limits" }
+                },
+            }
+            div { class: "flex items-end gap-2",
+                label { class: LABEL_CLASS,
+                    "Download KiB/s"
+                    input {
+                        class: INPUT_CLASS,
+                        r#type: "number",
+                        min: "0",
+                        placeholder: "Unlimited",
+                        value: "{download_limit}",
+                        oninput: move |event| download_limit.set(event.value.clone()),
+                    }
+                }
+                if summary.supports(Capability::UploadLimit) {
+                    render! {
+                        label { class: LABEL_CLASS,
+                            "Upload KiB/s"
+                            input {
+                                class: INPUT_CLASS,
+                                r#type: "number",
+                                min: "0",
+                                placeholder: "Unlimited",
+                                value: "{upload_limit}",
+                                oninput: move |event| upload_limit.set(event.value.clone()),
+                            }
+                        }
+                    }
+                } else {
+                    None
+                }
+                button {
+                    class: "rounded bg-indigo-600 px-4 py-2 text-sm font-medium text-white hover:bg-indigo-700",
+                    onclick: apply,
+                    "Apply"
+                }
+            }
+            error.get().as_ref().map(|error| rsx! {
+                p { class: "text-xs text-red-600 dark:text-red-400", "{error}" }
+            })
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct AlternativeSpeedsProps<'a> {
+    backend: &'a str,
+    enabled: bool,
+    refreshes: &'a UseState<u32>,
+    error: &'a UseState<Option<String>>,
+}
+
+
+pub fn AlternativeSpeeds<'a>(cx: Scope<'a, AlternativeSpeedsProps<'a>>) -> Element<'a> {
+    let AlternativeSpeedsProps { backend, enabled, refreshes, error } = cx.props;
+
+    let toggle = move |event: FormEvent| {
+        let (refreshes, error) = ((*refreshes).clone(), (*error).clone());
+        let backend = backend.to_string();
+        let enabled = event.value == "true";
+        cx.spawn(async move {
+            let result = set_alternative_speeds(backend, enabled).await;
+            error.set(result.err().map(|err| err.to_string()));
+            refreshes.modify(|refreshes| refreshes.wrapping_add(1));
+        });
+    };
+
+    return cx.render(rsx! {
+        label { class: "flex items-center gap-2 text-sm text-gray-700 dark:text-gray-200",
+            input {
+                r#type: "checkbox",
+                checked: *enabled,
+                onchange: toggle,
+            }
+            "Use the alternative speed limits"
+        }
+    });
+}
+
+/// An empty or zero input lifts the limit
+fn kibibytes_to_limit(input: &str) -> Option<u64> {
+    input
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|kibibytes| *kibibytes > 0)
+        .map(|kibibytes| kibibytes * 1024)
+}
+
+fn limit_to_kibibytes(limit: Option<u64>) -> String {
+    limit.map_or_else(String::new, |limit| limit.div_ceil(1024).to_string())
+}
@@ -1,5 +1,4 @@
-#![feature(future_join)]
-//! A read only client for `QBittorrent` and `SABnzbd`
+//! A dashboard for `QBittorrent`, `SABnzbd` and other download clients
 
 #![warn(
     rust_2018_idioms,
@@ -32,5 +31,7 @@ mod components;
 
 fn main() {
     dioxus_logger::init(LevelFilter::Info).expect("Failed to initialize logger");
+    #[cfg(feature = "ssr")]
+    crate::apis::init();
     LaunchBuilder::new(crate::app::app).launch();
 }